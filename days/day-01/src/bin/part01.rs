@@ -1,9 +1,14 @@
-use std::io::BufRead;
+use std::process::ExitCode;
 
-fn main() {
-    let reader = advent2024::open_file_cmd_line();
-    let (mut left, mut right) = day_01::parse_nums(reader.lines().map(|x| x.unwrap()));
-    left.sort();
-    right.sort();
-    println!("{}", day_01::distance(&left, &right));
+use advent2024::Part;
+use day_01::{explain_distance, render, Day01, Format};
+
+fn main() -> ExitCode {
+    advent2024::run_part_with::<Day01>(Part::One, "--explain", |_, (left, right), format| {
+        print!(
+            "{}",
+            render(&explain_distance(left, right), Format::from_flag(format)?)
+        );
+        Ok(())
+    })
 }
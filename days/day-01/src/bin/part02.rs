@@ -1,7 +1,14 @@
-use std::io::BufRead;
+use std::process::ExitCode;
 
-fn main() {
-    let reader = advent2024::open_file_cmd_line();
-    let (left, right) = day_01::parse_nums(reader.lines().map(|x| x.unwrap()));
-    println!("{}", day_01::similarity_score(&left, &right));
+use advent2024::Part;
+use day_01::{explain_similarity, render, Day01, Format};
+
+fn main() -> ExitCode {
+    advent2024::run_part_with::<Day01>(Part::Two, "--explain", |_, (left, right), format| {
+        print!(
+            "{}",
+            render(&explain_similarity(left, right), Format::from_flag(format)?)
+        );
+        Ok(())
+    })
 }
@@ -0,0 +1,150 @@
+//! Any number of equally long lists, compared pairwise.
+
+use std::{fmt::Display, hash::Hash};
+
+use advent2024::ParseError;
+
+use crate::{distance, distance_unsorted, parse_columns, similarity_score};
+
+/// Primitive integers the lists can hold. Distances are summed as `u128` and
+/// similarity scores as `i128` so that neither overflows on realistic input.
+pub trait Integer: Copy + Ord + Hash + Display + TryFrom<i128> {
+    /// The value as an `i128`, which every implementor fits in.
+    fn widen(self) -> i128;
+
+    fn abs_diff(self, other: Self) -> u128 {
+        self.widen().abs_diff(other.widen())
+    }
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                fn widen(self) -> i128 {
+                    self as i128
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+/// Lists read as the columns of the input, all of the same length.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSet<T> {
+    columns: Vec<Vec<T>>,
+}
+
+impl<T: Integer> ColumnSet<T> {
+    /// Panics if the columns differ in length.
+    pub fn new(columns: Vec<Vec<T>>) -> Self {
+        if let Some(first) = columns.first() {
+            assert!(
+                columns.iter().all(|c| c.len() == first.len()),
+                "columns differ in length"
+            );
+        }
+        ColumnSet { columns }
+    }
+
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        parse_columns(input.as_bytes()).map(Self::new)
+    }
+
+    /// Number of columns.
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.columns.first().map_or(0, Vec::len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn column(&self, idx: usize) -> &[T] {
+        &self.columns[idx]
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = &[T]> {
+        self.columns.iter().map(Vec::as_slice)
+    }
+
+    /// Total distance between columns `a` and `b` once both are sorted.
+    pub fn distance(&self, a: usize, b: usize) -> u128 {
+        distance_unsorted(&self.columns[a], &self.columns[b])
+    }
+
+    /// Similarity score of column `a` against the counts of column `b`.
+    pub fn similarity(&self, a: usize, b: usize) -> i128 {
+        similarity_score(&self.columns[a], &self.columns[b])
+    }
+
+    /// `distance(a, b)` for every pair of columns, sorting each only once.
+    pub fn distance_matrix(&self) -> Vec<Vec<u128>> {
+        let sorted: Vec<Vec<T>> = self
+            .columns
+            .iter()
+            .map(|c| {
+                let mut c = c.clone();
+                c.sort_unstable();
+                c
+            })
+            .collect();
+        sorted
+            .iter()
+            .map(|a| sorted.iter().map(|b| distance(a, b)).collect())
+            .collect()
+    }
+
+    /// `similarity(a, b)` for every pair of columns.
+    pub fn similarity_matrix(&self) -> Vec<Vec<i128>> {
+        (0..self.width())
+            .map(|a| (0..self.width()).map(|b| self.similarity(a, b)).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_column_set() {
+        let set = ColumnSet::<usize>::parse(include_str!("../extra/example.txt")).unwrap();
+        assert_eq!((set.width(), set.len()), (2, 6));
+        assert_eq!(set.distance(0, 1), 11);
+        assert_eq!(set.similarity(0, 1), 31);
+        assert_eq!(set.distance_matrix(), vec![vec![0, 11], vec![11, 0]]);
+        assert_eq!(set.similarity_matrix()[0][1], 31);
+    }
+
+    #[test]
+    fn test_signed_columns() {
+        let set = ColumnSet::<i32>::parse("-3 4 -3\n2 -1 9\n-3 0 -3\n").unwrap();
+        assert_eq!(set.width(), 3);
+        // Sorted: [-3, -3, 2], [-1, 0, 4], [-3, -3, 9]
+        assert_eq!(set.distance(0, 1), 2 + 3 + 2);
+        assert_eq!(set.distance(0, 2), 7);
+        assert_eq!(set.similarity(0, 2), -3 * 2 + -3 * 2);
+        let matrix = set.distance_matrix();
+        assert!((0..3).all(|a| (0..3).all(|b| matrix[a][b] == set.distance(a, b))));
+    }
+
+    #[test]
+    fn test_extreme_values() {
+        let set = ColumnSet::new(vec![vec![i64::MIN, i64::MIN], vec![i64::MAX, i64::MAX]]);
+        assert_eq!(set.distance(0, 1), 2 * u64::MAX as u128);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_uneven_columns() {
+        ColumnSet::new(vec![vec![1u8, 2], vec![3]]);
+    }
+}
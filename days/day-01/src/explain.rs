@@ -0,0 +1,197 @@
+//! Itemized versions of [`distance`](crate::distance) and
+//! [`similarity_score`](crate::similarity_score), rendered as a table or CSV.
+
+use advent2024::Error;
+
+use crate::Integer;
+
+/// One pair of the sorted lists and how far apart it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DistanceRow<T> {
+    pub left: T,
+    pub right: T,
+    pub difference: u128,
+}
+
+/// One value of the left list, how often it appears in the right list and
+/// what it adds to the score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimilarityRow<T> {
+    pub left: T,
+    pub count: usize,
+    pub contribution: i128,
+}
+
+/// Rows of the breakdown summing to the answer, in the order of the sorted
+/// lists.
+pub fn explain_distance<T: Integer>(left: &[T], right: &[T]) -> Vec<DistanceRow<T>> {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    left.sort_unstable();
+    right.sort_unstable();
+    left.into_iter()
+        .zip(right)
+        .map(|(left, right)| DistanceRow {
+            left,
+            right,
+            difference: left.abs_diff(right),
+        })
+        .collect()
+}
+
+/// Rows of the breakdown summing to the answer, in the order of `left`.
+pub fn explain_similarity<T: Integer>(left: &[T], right: &[T]) -> Vec<SimilarityRow<T>> {
+    let counts = crate::counts(right);
+    left.iter()
+        .map(|&left| {
+            let count = counts.get(&left).copied().unwrap_or(0);
+            SimilarityRow {
+                left,
+                count: count as usize,
+                contribution: left.widen() * count,
+            }
+        })
+        .collect()
+}
+
+/// A row of a breakdown, as the cells of a table.
+pub trait Row {
+    const HEADERS: [&'static str; 3];
+
+    fn cells(&self) -> [String; 3];
+}
+
+impl<T: Integer> Row for DistanceRow<T> {
+    const HEADERS: [&'static str; 3] = ["left", "right", "difference"];
+
+    fn cells(&self) -> [String; 3] {
+        [
+            self.left.to_string(),
+            self.right.to_string(),
+            self.difference.to_string(),
+        ]
+    }
+}
+
+impl<T: Integer> Row for SimilarityRow<T> {
+    const HEADERS: [&'static str; 3] = ["left", "count", "contribution"];
+
+    fn cells(&self) -> [String; 3] {
+        [
+            self.left.to_string(),
+            self.count.to_string(),
+            self.contribution.to_string(),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Table,
+    Csv,
+}
+
+impl Format {
+    /// `--explain` alone or `--explain=table` for a table, `--explain=csv`
+    /// for CSV.
+    pub fn from_flag(value: Option<&str>) -> Result<Self, Error> {
+        match value {
+            None | Some("table") => Ok(Format::Table),
+            Some("csv") => Ok(Format::Csv),
+            Some(other) => Err(Error::Usage(format!(
+                "unknown explain format {other}, expected table or csv"
+            ))),
+        }
+    }
+}
+
+/// The rows under a header, with right aligned columns for a table.
+pub fn render<R: Row>(rows: &[R], format: Format) -> String {
+    let cells: Vec<[String; 3]> = rows.iter().map(Row::cells).collect();
+    let header = R::HEADERS.map(str::to_owned);
+    let mut out = String::new();
+    match format {
+        Format::Csv => {
+            for line in std::iter::once(&header).chain(&cells) {
+                out.push_str(&line.join(","));
+                out.push('\n');
+            }
+        }
+        Format::Table => {
+            let mut widths = header.clone().map(|h| h.len());
+            for line in &cells {
+                for (width, cell) in widths.iter_mut().zip(line) {
+                    *width = (*width).max(cell.len());
+                }
+            }
+            let rule = widths.map(|w| "-".repeat(w));
+            for line in [&header, &rule].into_iter().chain(&cells) {
+                let padded: Vec<String> = line
+                    .iter()
+                    .zip(widths)
+                    .map(|(cell, width)| format!("{cell:>width$}"))
+                    .collect();
+                out.push_str(&padded.join("  "));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{distance_unsorted, similarity_score};
+
+    const LEFT: [usize; 6] = [3, 4, 2, 1, 3, 3];
+    const RIGHT: [usize; 6] = [4, 3, 5, 3, 9, 3];
+
+    #[test]
+    fn test_explain_distance() {
+        let rows = explain_distance(&LEFT, &RIGHT);
+        let total: u128 = rows.iter().map(|r| r.difference).sum();
+        assert_eq!(total, distance_unsorted(&LEFT, &RIGHT));
+        assert_eq!(
+            rows[0],
+            DistanceRow {
+                left: 1,
+                right: 3,
+                difference: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_explain_similarity() {
+        let rows = explain_similarity(&LEFT, &RIGHT);
+        let total: i128 = rows.iter().map(|r| r.contribution).sum();
+        assert_eq!(total, similarity_score(&LEFT, &RIGHT));
+        assert_eq!(
+            rows[1],
+            SimilarityRow {
+                left: 4,
+                count: 1,
+                contribution: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let rows = explain_similarity(&[3, 10], &[3, 3]);
+        assert_eq!(
+            render(&rows, Format::Csv),
+            "left,count,contribution\n3,2,6\n10,0,0\n"
+        );
+        assert_eq!(
+            render(&rows, Format::Table),
+            "left  count  contribution\n\
+             ----  -----  ------------\n   \
+                3      2             6\n  \
+               10      0             0\n"
+        );
+        assert_eq!(Format::from_flag(Some("csv")).unwrap(), Format::Csv);
+        assert!(Format::from_flag(Some("json")).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+//! Similarity score kept up to date while values come and go, with the
+//! sorted-pair distance recomputed on demand.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::Integer;
+
+/// Which of the two lists an update applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Both lists as counts of each value. The similarity score is
+/// `sum(v * left(v) * right(v))` over every value `v`, so adding or removing
+/// one value only moves it by `v` times the other list's count of `v`, which
+/// makes every update O(1).
+///
+/// Only the similarity score is incremental. The sorted-pair distance has no
+/// such shortcut, as a single insert can re-pair every larger value, so it is
+/// recomputed on every call to [`distance`]. When built [`with_distance`],
+/// ordered counts are kept alongside, which makes updates O(log n) and each
+/// [`distance`] call O(distinct values) rather than a sort of both lists.
+///
+/// [`with_distance`]: IncrementalSimilarity::with_distance
+/// [`distance`]: IncrementalSimilarity::distance
+#[derive(Debug, Clone)]
+pub struct IncrementalSimilarity<T> {
+    counts: [HashMap<T, usize>; 2],
+    lens: [usize; 2],
+    score: i128,
+    ordered: Option<[BTreeMap<T, usize>; 2]>,
+}
+
+impl<T: Integer> Default for IncrementalSimilarity<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn idx(side: Side) -> usize {
+    match side {
+        Side::Left => 0,
+        Side::Right => 1,
+    }
+}
+
+impl<T: Integer> IncrementalSimilarity<T> {
+    /// Empty lists, tracking only the similarity score.
+    pub fn new() -> Self {
+        IncrementalSimilarity {
+            counts: Default::default(),
+            lens: [0; 2],
+            score: 0,
+            ordered: None,
+        }
+    }
+
+    /// Empty lists, also tracking what [`distance`](Self::distance) needs.
+    pub fn with_distance() -> Self {
+        IncrementalSimilarity {
+            ordered: Some(Default::default()),
+            ..Self::new()
+        }
+    }
+
+    /// Starts from existing lists, tracking the distance if `distance` is set.
+    pub fn from_lists(left: &[T], right: &[T], distance: bool) -> Self {
+        let mut this = if distance {
+            Self::with_distance()
+        } else {
+            Self::new()
+        };
+        left.iter().for_each(|v| this.insert(Side::Left, *v));
+        right.iter().for_each(|v| this.insert(Side::Right, *v));
+        this
+    }
+
+    pub fn score(&self) -> i128 {
+        self.score
+    }
+
+    pub fn len(&self, side: Side) -> usize {
+        self.lens[idx(side)]
+    }
+
+    pub fn count(&self, side: Side, value: T) -> usize {
+        self.counts[idx(side)].get(&value).copied().unwrap_or(0)
+    }
+
+    pub fn insert(&mut self, side: Side, value: T) {
+        let this = idx(side);
+        let other = self.counts[1 - this].get(&value).copied().unwrap_or(0);
+        self.score += value.widen() * other as i128;
+        *self.counts[this].entry(value).or_insert(0) += 1;
+        self.lens[this] += 1;
+        if let Some(ordered) = &mut self.ordered {
+            *ordered[this].entry(value).or_insert(0) += 1;
+        }
+    }
+
+    /// Removes one occurrence of `value`, returning whether there was one.
+    pub fn remove(&mut self, side: Side, value: T) -> bool {
+        let this = idx(side);
+        if !self.counts[this].decrement(value) {
+            return false;
+        }
+        let other = self.counts[1 - this].get(&value).copied().unwrap_or(0);
+        self.score -= value.widen() * other as i128;
+        self.lens[this] -= 1;
+        if let Some(ordered) = &mut self.ordered {
+            ordered[this].decrement(value);
+        }
+        true
+    }
+
+    /// The [`distance`](crate::distance) between both lists once sorted,
+    /// `None` unless built to track it. Not kept up to date by the updates:
+    /// every call walks the ordered counts, in O(distinct values).
+    pub fn distance(&self) -> Option<u128> {
+        let [left, right] = self.ordered.as_ref()?;
+        let mut left = left.iter().map(|(v, n)| (*v, *n));
+        let mut right = right.iter().map(|(v, n)| (*v, *n));
+        let (mut l, mut r) = (left.next(), right.next());
+        let mut total = 0;
+        // Pair off runs of equal values, as many at a time as both have left
+        while let (Some((lv, ln)), Some((rv, rn))) = (l, r) {
+            let paired = ln.min(rn);
+            total += lv.abs_diff(rv) * paired as u128;
+            l = if ln > paired {
+                Some((lv, ln - paired))
+            } else {
+                left.next()
+            };
+            r = if rn > paired {
+                Some((rv, rn - paired))
+            } else {
+                right.next()
+            };
+        }
+        Some(total)
+    }
+}
+
+trait Counts<T> {
+    /// Takes one off the count of `value`, dropping it at zero.
+    fn decrement(&mut self, value: T) -> bool;
+}
+
+macro_rules! impl_counts {
+    ($($map:ident),*) => {
+        $(
+            impl<T: Integer> Counts<T> for $map<T, usize> {
+                fn decrement(&mut self, value: T) -> bool {
+                    match self.get_mut(&value) {
+                        Some(1) => {
+                            self.remove(&value);
+                            true
+                        }
+                        Some(n) => {
+                            *n -= 1;
+                            true
+                        }
+                        None => false,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_counts!(HashMap, BTreeMap);
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{distance_unsorted, similarity_score};
+
+    #[test]
+    fn test_incremental_similarity() {
+        let left = [3usize, 4, 2, 1, 3, 3];
+        let right = [4usize, 3, 5, 3, 9, 3];
+        let mut inc = IncrementalSimilarity::from_lists(&left, &right, true);
+        assert_eq!((inc.score(), inc.distance()), (31, Some(11)));
+
+        assert!(inc.remove(Side::Right, 3));
+        assert_eq!(inc.score(), 31 - 3 * 3);
+        assert!(!inc.remove(Side::Left, 9));
+        inc.insert(Side::Left, 9);
+        assert_eq!(inc.score(), 31 - 3 * 3 + 9);
+        assert_eq!(inc.count(Side::Left, 9), 1);
+        assert_eq!((inc.len(Side::Left), inc.len(Side::Right)), (7, 5));
+
+        assert_eq!(IncrementalSimilarity::<usize>::new().distance(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn matches_recomputing(
+            ops in prop::collection::vec((any::<bool>(), any::<bool>(), -20i32..20), 0..200),
+        ) {
+            let mut inc = IncrementalSimilarity::with_distance();
+            let mut lists: [Vec<i32>; 2] = Default::default();
+            for (right, insert, value) in ops {
+                let side = if right { Side::Right } else { Side::Left };
+                let list = &mut lists[idx(side)];
+                if insert {
+                    inc.insert(side, value);
+                    list.push(value);
+                } else {
+                    let pos = list.iter().position(|v| *v == value);
+                    prop_assert_eq!(inc.remove(side, value), pos.is_some());
+                    if let Some(pos) = pos {
+                        list.swap_remove(pos);
+                    }
+                }
+                let [left, right] = &lists;
+                prop_assert_eq!(inc.score(), similarity_score(left, right));
+                prop_assert_eq!(inc.distance(), Some(distance_unsorted(left, right)));
+            }
+        }
+    }
+}
@@ -1,26 +1,92 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt::Display};
 
-pub fn distance(left: &[usize], right: &[usize]) -> usize {
+use advent2024::{InputSource, ParseError, Solution};
+
+mod columns;
+mod explain;
+mod incremental;
+mod parse;
+mod sort;
+
+pub use columns::{ColumnSet, Integer};
+pub use explain::{
+    explain_distance, explain_similarity, render, DistanceRow, Format, Row, SimilarityRow,
+};
+pub use incremental::{IncrementalSimilarity, Side};
+pub use parse::{parse_columns, read_columns};
+pub use sort::distance_unsorted;
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+
+    type Input = (Vec<usize>, Vec<usize>);
+
+    fn default_input() -> InputSource {
+        advent2024::default_input!()
+    }
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut columns = parse_columns(input.as_bytes())?;
+        match columns.len() {
+            0 => Ok((Vec::new(), Vec::new())),
+            2 => {
+                let right = columns.pop().unwrap();
+                let left = columns.pop().unwrap();
+                Ok((left, right))
+            }
+            _ => {
+                let first = input.lines().find(|l| !l.trim().is_empty()).unwrap();
+                Err(ParseError::new(input, first, "two columns"))
+            }
+        }
+    }
+
+    fn part1((left, right): &Self::Input) -> impl Display {
+        distance_unsorted(left, right)
+    }
+
+    fn part2((left, right): &Self::Input) -> impl Display {
+        similarity_score(left, right)
+    }
+}
+
+pub fn distance<T: Integer>(left: &[T], right: &[T]) -> u128 {
     left.iter()
         .zip(right.iter())
-        .map(|(l, r)| if l > r { l - r } else { r - l })
+        .map(|(l, r)| l.abs_diff(*r))
         .sum()
 }
 
-pub fn parse_nums<S: AsRef<str>, I: Iterator<Item = S>>(s: I) -> (Vec<usize>, Vec<usize>) {
+fn parse_num(line: &str, token: Option<&str>) -> Result<usize, ParseError> {
+    let token = token.ok_or_else(|| ParseError::end_of_line(line, "a number"))?;
+    token
+        .parse()
+        .map_err(|_| ParseError::new(line, token, "a number"))
+}
+
+pub fn try_parse_nums<S: AsRef<str>, I: Iterator<Item = S>>(
+    s: I,
+) -> Result<(Vec<usize>, Vec<usize>), ParseError> {
     let mut lefts = Vec::new();
     let mut rights = Vec::new();
-    for line in s {
-        let mut num_iter = line.as_ref().split_whitespace();
-        let left = num_iter.next().unwrap().parse::<usize>().unwrap();
-        let right = num_iter.next().unwrap().parse::<usize>().unwrap();
+    for (idx, line) in s.enumerate() {
+        let line = line.as_ref();
+        let mut num_iter = line.split_whitespace();
+        let left = parse_num(line, num_iter.next()).map_err(|e| e.on_line(idx + 1))?;
+        let right = parse_num(line, num_iter.next()).map_err(|e| e.on_line(idx + 1))?;
         lefts.push(left);
         rights.push(right);
     }
-    (lefts, rights)
+    Ok((lefts, rights))
+}
+
+pub fn parse_nums<S: AsRef<str>, I: Iterator<Item = S>>(s: I) -> (Vec<usize>, Vec<usize>) {
+    try_parse_nums(s).unwrap()
 }
 
-fn counts(xs: &[usize]) -> HashMap<usize, usize> {
+fn counts<T: Integer>(xs: &[T]) -> HashMap<T, i128> {
     let mut counts = HashMap::new();
     for x in xs {
         *counts.entry(*x).or_insert(0) += 1;
@@ -28,13 +94,15 @@ fn counts(xs: &[usize]) -> HashMap<usize, usize> {
     counts
 }
 
-pub fn similarity_score(left: &[usize], right: &[usize]) -> usize {
+pub fn similarity_score<T: Integer>(left: &[T], right: &[T]) -> i128 {
     let right_counts = counts(right);
     left.iter()
-        .flat_map(|l| right_counts.get(l).map(|r| r * l))
+        .flat_map(|l| right_counts.get(l).map(|r| r * l.widen()))
         .sum()
 }
 
+advent2024::example_tests!(Day01);
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -50,12 +118,7 @@ mod test {
 
     #[test]
     fn test_day01() {
-        let example = "3   4
-4   3
-2   5
-1   3
-3   9
-3   3";
+        let example = include_str!("../extra/example.txt");
         let (mut left, mut right) = parse_nums(example.lines());
         assert_eq!(left, vec![3, 4, 2, 1, 3, 3]);
         assert_eq!(right, vec![4, 3, 5, 3, 9, 3]);
@@ -66,4 +129,23 @@ mod test {
         right.sort();
         assert_eq!(distance(&left, &right), 11)
     }
+
+    #[test]
+    fn test_try_parse_nums() {
+        let err = try_parse_nums("3   4\n4   x3\n".lines()).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "x3"));
+
+        let err = try_parse_nums("3   4\n4".lines()).unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, ""));
+    }
+
+    #[test]
+    fn test_parse() {
+        let input = include_str!("../extra/input");
+        assert_eq!(Day01::parse(input).unwrap(), parse_nums(input.lines()));
+        assert_eq!(Day01::parse("").unwrap(), (vec![], vec![]));
+
+        let err = Day01::parse("\n1 2 3\n").unwrap_err();
+        assert_eq!((err.line, err.expected), (2, "two columns"));
+    }
 }
@@ -0,0 +1,200 @@
+//! Byte-level parsing of whitespace separated columns of numbers, without
+//! allocating per line.
+
+use std::{io::BufRead, path::PathBuf};
+
+use advent2024::{Error, ParseError};
+
+use crate::Integer;
+
+/// Builds columns one line at a time, the first non-blank line fixing how
+/// many columns every other line must have.
+#[derive(Debug)]
+struct ColumnParser<T> {
+    columns: Vec<Vec<T>>,
+    width: Option<usize>,
+}
+
+impl<T> Default for ColumnParser<T> {
+    fn default() -> Self {
+        ColumnParser {
+            columns: Vec::new(),
+            width: None,
+        }
+    }
+}
+
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+/// Decimal number with an optional leading `-`, `None` if it is malformed or
+/// does not fit in `T`.
+fn number<T: Integer>(token: &[u8]) -> Option<T> {
+    let (negative, digits) = match token.strip_prefix(b"-") {
+        Some(digits) => (true, digits),
+        None => (false, token),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    let magnitude = digits.iter().try_fold(0i128, |acc, &b| {
+        let digit = b.checked_sub(b'0').filter(|d| *d < 10)?;
+        acc.checked_mul(10)?.checked_add(digit as i128)
+    })?;
+    T::try_from(if negative { -magnitude } else { magnitude }).ok()
+}
+
+fn error(
+    line_number: usize,
+    line: &[u8],
+    start: usize,
+    end: usize,
+    expected: &'static str,
+) -> ParseError {
+    ParseError {
+        line: line_number,
+        column: start + 1,
+        text: String::from_utf8_lossy(&line[start..end]).into_owned(),
+        expected,
+    }
+}
+
+impl<T: Integer> ColumnParser<T> {
+    fn line(&mut self, line_number: usize, line: &[u8]) -> Result<(), ParseError> {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut column = 0;
+        let mut pos = 0;
+        loop {
+            while pos < line.len() && is_blank(line[pos]) {
+                pos += 1;
+            }
+            if pos == line.len() {
+                break;
+            }
+            let start = pos;
+            while pos < line.len() && !is_blank(line[pos]) {
+                pos += 1;
+            }
+            let value = number(&line[start..pos])
+                .ok_or_else(|| error(line_number, line, start, pos, "a number"))?;
+
+            match self.width {
+                Some(width) if column >= width => {
+                    return Err(error(line_number, line, start, pos, "end of line"))
+                }
+                Some(_) => {}
+                None => self.columns.push(Vec::new()),
+            }
+            self.columns[column].push(value);
+            column += 1;
+        }
+
+        match self.width {
+            // Blank lines carry no row
+            _ if column == 0 => Ok(()),
+            None => {
+                self.width = Some(column);
+                Ok(())
+            }
+            Some(width) if column < width => {
+                Err(error(line_number, line, line.len(), line.len(), "a number"))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+}
+
+/// Parses rows of whitespace separated numbers into columns. Lines may end in
+/// `\n` or `\r\n`, numbers may be separated by spaces or tabs, and blank lines
+/// are skipped. Every row must have as many numbers as the first, and every
+/// number must fit in `T`.
+pub fn parse_columns<T: Integer>(buf: &[u8]) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut parser = ColumnParser::default();
+    for (idx, line) in buf.split(|&b| b == b'\n').enumerate() {
+        parser.line(idx + 1, line)?;
+    }
+    Ok(parser.columns)
+}
+
+/// [`parse_columns`] over a reader, reusing a single line buffer.
+pub fn read_columns<T: Integer, R: BufRead>(mut reader: R) -> Result<Vec<Vec<T>>, Error> {
+    let mut parser = ColumnParser::default();
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    loop {
+        line.clear();
+        let read = reader
+            .read_until(b'\n', &mut line)
+            .map_err(|source| Error::Io {
+                path: PathBuf::from("<reader>"),
+                source,
+            })?;
+        if read == 0 {
+            return Ok(parser.columns);
+        }
+        line_number += 1;
+        let line = line.strip_suffix(b"\n").unwrap_or(&line);
+        parser.line(line_number, line)?;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_columns() {
+        let expected: Vec<Vec<usize>> = vec![vec![3, 4, 2], vec![4, 3, 5]];
+        assert_eq!(
+            parse_columns::<usize>(b"3   4\n4   3\n2   5").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_columns::<usize>(b"3\t4\r\n4 \t 3\r\n2   5\r\n\r\n\n").unwrap(),
+            expected
+        );
+        assert_eq!(
+            parse_columns::<usize>(b"1 2 3\n4 5 6\n").unwrap(),
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+        );
+        assert_eq!(
+            parse_columns::<i8>(b"-3 4\n-128 127\n").unwrap(),
+            vec![vec![-3, -128], vec![4, 127]]
+        );
+        assert!(parse_columns::<usize>(b"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_columns_errors() {
+        let err = parse_columns::<usize>(b"3   4\n4   3x\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "3x"));
+
+        let err = parse_columns::<usize>(b"3   4\n\n4   3   7\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.expected), (3, 9, "end of line"));
+
+        let err = parse_columns::<usize>(b"3   4\r\n4\r\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, ""));
+
+        let err = parse_columns::<usize>(b"99999999999999999999999 1\n").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+
+        let err = parse_columns::<u8>(b"1 -2\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 3, "-2"));
+        let err = parse_columns::<i8>(b"1 -\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 3, "-"));
+    }
+
+    #[test]
+    fn test_read_columns() {
+        let input = include_bytes!("../extra/input");
+        assert_eq!(
+            read_columns::<usize, _>(&input[..]).unwrap(),
+            parse_columns::<usize>(input).unwrap()
+        );
+        assert!(matches!(
+            read_columns::<usize, _>(&b"1 2\n3\n"[..]),
+            Err(Error::Parse(ParseError { line: 2, .. }))
+        ));
+    }
+}
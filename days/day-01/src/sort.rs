@@ -0,0 +1,173 @@
+//! Distance between unsorted lists without a comparison sort where the values
+//! allow it.
+
+use crate::Integer;
+
+/// Below this many values a comparison sort beats setting up radix passes.
+const MIN_RADIX_LEN: usize = 256;
+
+/// How many times wider than the list the value range may be for a counting
+/// sort, keeping its table of counts proportional to the list.
+const COUNTING_RANGE_PER_KEY: u64 = 16;
+
+/// Each value minus the smallest of both lists, so that every key fits in a
+/// `u64` and differences between keys equal differences between values.
+fn keys<T: Integer>(left: &[T], right: &[T]) -> (Vec<u64>, Vec<u64>, u64) {
+    let min = left.iter().chain(right).min().map_or(0, |m| m.widen());
+    let max = left.iter().chain(right).max().map_or(0, |m| m.widen());
+    let key = |x: &T| (x.widen() - min) as u64;
+    let left = left.iter().map(key).collect();
+    let right = right.iter().map(key).collect();
+    (left, right, (max - min) as u64)
+}
+
+fn counting_sort(keys: &mut [u64], range: u64) {
+    let mut counts = vec![0usize; range as usize + 1];
+    for &k in keys.iter() {
+        counts[k as usize] += 1;
+    }
+    let mut idx = 0;
+    for (k, &count) in counts.iter().enumerate() {
+        keys[idx..idx + count].fill(k as u64);
+        idx += count;
+    }
+}
+
+/// Least significant digit first, one byte per pass, skipping the bytes
+/// above `range`.
+fn radix_sort(keys: &mut Vec<u64>, range: u64) {
+    let passes = (u64::BITS - range.leading_zeros()).div_ceil(8);
+    let mut scratch = vec![0u64; keys.len()];
+    for pass in 0..passes {
+        let shift = pass * 8;
+        let mut offsets = [0usize; 257];
+        for &k in keys.iter() {
+            offsets[((k >> shift) & 0xff) as usize + 1] += 1;
+        }
+        for byte in 0..256 {
+            offsets[byte + 1] += offsets[byte];
+        }
+        for &k in keys.iter() {
+            let byte = ((k >> shift) & 0xff) as usize;
+            scratch[offsets[byte]] = k;
+            offsets[byte] += 1;
+        }
+        std::mem::swap(keys, &mut scratch);
+    }
+}
+
+fn uses_counting(len: usize, range: u64) -> bool {
+    range <= (len as u64).saturating_mul(COUNTING_RANGE_PER_KEY)
+}
+
+fn sort_keys(keys: &mut Vec<u64>, range: u64) {
+    if uses_counting(keys.len(), range) {
+        counting_sort(keys, range);
+    } else if keys.len() >= MIN_RADIX_LEN {
+        radix_sort(keys, range);
+    } else {
+        keys.sort_unstable();
+    }
+}
+
+/// Same as sorting both lists and taking their [`distance`](crate::distance),
+/// but in linear time using a counting sort when the values span a range at
+/// most 16 times as wide as the lists are long, or a radix sort on long lists
+/// otherwise. Short lists of widely spread values fall back to a comparison
+/// sort.
+pub fn distance_unsorted<T: Integer>(left: &[T], right: &[T]) -> u128 {
+    let (mut left, mut right, range) = keys(left, right);
+    sort_keys(&mut left, range);
+    sort_keys(&mut right, range);
+    left.iter()
+        .zip(&right)
+        .map(|(l, r)| l.abs_diff(*r) as u128)
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::distance;
+
+    fn sorted_distance<T: Integer>(left: &[T], right: &[T]) -> u128 {
+        let mut left = left.to_vec();
+        let mut right = right.to_vec();
+        left.sort();
+        right.sort();
+        distance(&left, &right)
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut rng = 1u64;
+        let mut keys: Vec<u64> = (0..1000)
+            .map(|_| {
+                rng ^= rng << 13;
+                rng ^= rng >> 7;
+                rng ^= rng << 17;
+                rng
+            })
+            .collect();
+        let mut expected = keys.clone();
+        expected.sort_unstable();
+
+        radix_sort(&mut keys, u64::MAX);
+        assert_eq!(keys, expected);
+
+        let mut keys: Vec<u64> = expected.iter().rev().map(|k| k % 100).collect();
+        counting_sort(&mut keys, 99);
+        assert!(keys.is_sorted());
+    }
+
+    #[test]
+    fn test_uses_counting() {
+        assert!(uses_counting(6, 8));
+        assert!(uses_counting(1000, 16_000));
+        assert!(!uses_counting(1000, 90_000));
+        assert!(!uses_counting(1, 60_000));
+        assert!(!uses_counting(0, 1));
+        assert!(uses_counting(usize::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn test_distance_unsorted() {
+        let left = [3usize, 4, 2, 1, 3, 3];
+        let right = [4usize, 3, 5, 3, 9, 3];
+        assert_eq!(distance_unsorted(&left, &right), 11);
+        assert_eq!(distance_unsorted::<i64>(&[], &[]), 0);
+        assert_eq!(distance_unsorted(&[0u32], &[60_000]), 60_000);
+        assert_eq!(
+            distance_unsorted(&[i64::MIN], &[i64::MAX]),
+            u64::MAX as u128
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn counting_matches_sort(
+            left in prop::collection::vec(-50i32..50, 0..500),
+            right in prop::collection::vec(-50i32..50, 0..500),
+        ) {
+            prop_assert_eq!(distance_unsorted(&left, &right), sorted_distance(&left, &right));
+        }
+
+        #[test]
+        fn radix_matches_sort(
+            left in prop::collection::vec(any::<i64>(), 0..1000),
+            right in prop::collection::vec(any::<i64>(), 0..1000),
+        ) {
+            prop_assert_eq!(distance_unsorted(&left, &right), sorted_distance(&left, &right));
+        }
+
+        #[test]
+        fn unsigned_matches_sort(
+            left in prop::collection::vec(any::<u32>(), 0..1000),
+            right in prop::collection::vec(0u32..100_000, 0..1000),
+        ) {
+            prop_assert_eq!(distance_unsorted(&left, &right), sorted_distance(&left, &right));
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Every report of an input in one flat buffer, evaluated in bulk.
+
+use advent2024::ParseError;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::SafetyPolicy;
+
+/// Reports stored back to back, report `i` being
+/// `levels[offsets[i]..offsets[i + 1]]`, so that millions of them take two
+/// allocations rather than one each.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reports {
+    levels: Vec<isize>,
+    offsets: Vec<usize>,
+}
+
+impl Default for Reports {
+    fn default() -> Self {
+        Reports {
+            levels: Vec::new(),
+            offsets: vec![0],
+        }
+    }
+}
+
+impl Reports {
+    /// One report per line. A blank line is an error rather than a report
+    /// with no levels, which would count as safe.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut reports = Reports::default();
+        for (idx, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                return Err(ParseError::end_of_line(line, "a level").on_line(idx + 1));
+            }
+            for token in line.split_ascii_whitespace() {
+                let level = token
+                    .parse()
+                    .map_err(|_| ParseError::new(line, token, "a level").on_line(idx + 1))?;
+                reports.levels.push(level);
+            }
+            reports.offsets.push(reports.levels.len());
+        }
+        Ok(reports)
+    }
+
+    pub fn push(&mut self, levels: &[isize]) {
+        self.levels.extend_from_slice(levels);
+        self.offsets.push(self.levels.len());
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, idx: usize) -> &[isize] {
+        &self.levels[self.offsets[idx]..self.offsets[idx + 1]]
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &[isize]> + '_ {
+        self.offsets.windows(2).map(|w| &self.levels[w[0]..w[1]])
+    }
+
+    /// Whether each report is safe under `policy` once up to `tolerance`
+    /// levels are removed. With the `parallel` feature the reports are split
+    /// across threads, which gives the same answers as going one by one.
+    pub fn safe(&self, policy: &SafetyPolicy, tolerance: usize) -> Vec<bool> {
+        let check = |idx| {
+            let levels = self.get(idx);
+            match tolerance {
+                0 => policy.is_safe(levels),
+                k => policy.dampen(levels, k).is_some(),
+            }
+        };
+        #[cfg(feature = "parallel")]
+        let safe = (0..self.len()).into_par_iter().map(check).collect();
+        #[cfg(not(feature = "parallel"))]
+        let safe = (0..self.len()).map(check).collect();
+        safe
+    }
+
+    pub fn count_safe(&self, policy: &SafetyPolicy, tolerance: usize) -> usize {
+        self.safe(policy, tolerance)
+            .into_iter()
+            .filter(|s| *s)
+            .count()
+    }
+}
+
+impl<'a> FromIterator<&'a [isize]> for Reports {
+    fn from_iter<I: IntoIterator<Item = &'a [isize]>>(iter: I) -> Self {
+        let mut reports = Reports::default();
+        iter.into_iter().for_each(|levels| reports.push(levels));
+        reports
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{is_safe, parse_level};
+
+    #[test]
+    fn test_parse() {
+        let reports = Reports::parse("7 6 4\n1 2 7 8\n").unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports.get(0), [7, 6, 4]);
+        assert_eq!(reports.iter().last(), Some(&[1, 2, 7, 8][..]));
+
+        let err = Reports::parse("7 6 4\n1 2 x 8\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 5, "x"));
+        let err = Reports::parse("7 6 4\n \n1 2 7 8\n").unwrap_err();
+        assert_eq!((err.line, err.expected), (2, "a level"));
+    }
+
+    /// The serial path the binaries used to take: each line parsed on its
+    /// own, then every single removal tried in turn.
+    fn serial(input: &str) -> (Vec<bool>, Vec<bool>) {
+        let levels: Vec<_> = input.lines().map(parse_level).collect();
+        let safe = levels.iter().map(|l| is_safe(l)).collect();
+        let tolerable = levels
+            .iter()
+            .map(|l| {
+                is_safe(l)
+                    || (0..l.len()).any(|idx| {
+                        let mut removed = l.clone();
+                        removed.remove(idx);
+                        is_safe(&removed)
+                    })
+            })
+            .collect();
+        (safe, tolerable)
+    }
+
+    #[test]
+    fn test_matches_serial() {
+        let input = include_str!("../extra/input");
+        let reports = Reports::parse(input).unwrap();
+        let levels: Vec<_> = input.lines().map(parse_level).collect();
+        assert_eq!(
+            reports,
+            levels.iter().map(Vec::as_slice).collect::<Reports>()
+        );
+
+        let policy = SafetyPolicy::default();
+        let (safe, tolerable) = serial(input);
+        assert_eq!(reports.safe(&policy, 0), safe);
+        assert_eq!(reports.safe(&policy, 1), tolerable);
+        let two: Vec<_> = levels
+            .iter()
+            .map(|l| policy.dampen_brute_force(l, 2).is_some())
+            .collect();
+        assert_eq!(reports.safe(&policy, 2), two);
+    }
+
+    /// Enough reports for rayon to split them across every thread.
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_serial() {
+        let input = include_str!("../extra/input").repeat(50);
+        let reports = Reports::parse(&input).unwrap();
+        let (safe, tolerable) = serial(&input);
+        let policy = SafetyPolicy::default();
+        assert_eq!(reports.safe(&policy, 0), safe);
+        assert_eq!(reports.safe(&policy, 1), tolerable);
+        assert_eq!(
+            reports.count_safe(&policy, 1),
+            tolerable.iter().filter(|s| **s).count()
+        );
+    }
+}
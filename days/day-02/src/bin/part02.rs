@@ -1,14 +1,14 @@
-use std::io::BufRead;
+use std::process::ExitCode;
 
-fn main() {
-    let reader = advent2024::open_file_cmd_line();
-    let mut safe_reports = 0;
-    for line in reader.lines() {
-        let line = line.unwrap();
-        let levels = day_02::parse_level(&line);
-        if day_02::is_safe_tolerable(&levels) {
-            safe_reports += 1;
+use advent2024::Part;
+use day_02::{Day02, SafetyPolicy};
+
+fn main() -> ExitCode {
+    advent2024::run_part_with::<Day02>(Part::Two, "--report", |_, reports, _| {
+        let policy = SafetyPolicy::default();
+        for (idx, levels) in reports.iter().enumerate() {
+            println!("line {}: {}", idx + 1, policy.analyze_report(levels, 1));
         }
-    }
-    println!("{safe_reports}");
+        Ok(())
+    })
 }
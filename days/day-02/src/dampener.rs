@@ -0,0 +1,234 @@
+//! The Problem Dampener: making a report safe by removing up to `k` levels.
+
+use crate::SafetyPolicy;
+
+/// No kept level before this one, every earlier level was removed.
+const FIRST: usize = usize::MAX;
+
+impl SafetyPolicy {
+    /// The fewest indices, at most `k`, whose removal makes `xs` safe, or
+    /// `None` if more than `k` would have to go.
+    ///
+    /// Whether a level can follow another only depends on those two levels,
+    /// so for each direction it is enough to know the fewest removals of a
+    /// safe run of kept levels ending at each index. The level kept before
+    /// `i` is one of the `k + 1` before it, which makes this O(n·k) time and
+    /// O(n) space. Report rules see the whole report, so with any of them
+    /// this falls back to [`dampen_brute_force`](SafetyPolicy::dampen_brute_force).
+    pub fn dampen(&self, xs: &[isize], k: usize) -> Option<Vec<usize>> {
+        if self.report_rules().next().is_some() {
+            return self.dampen_brute_force(xs, k);
+        }
+        if xs.is_empty() {
+            return Some(Vec::new());
+        }
+        [true, false]
+            .into_iter()
+            .filter_map(|increasing| self.dampen_direction(xs, k, increasing))
+            .min_by_key(Vec::len)
+    }
+
+    fn dampen_direction(&self, xs: &[isize], k: usize, increasing: bool) -> Option<Vec<usize>> {
+        let n = xs.len();
+        // The fewest removals of a safe run ending at `i`, and the kept level
+        // before `i` in that run
+        let mut fewest = vec![0; n];
+        let mut prev = vec![FIRST; n];
+        for i in 0..n {
+            let mut best = (i, FIRST);
+            for j in i.saturating_sub(k + 1)..i {
+                let removals = fewest[j] + (i - j - 1);
+                if removals < best.0 && self.pair_ok(xs[j], xs[i], increasing) {
+                    best = (removals, j);
+                }
+            }
+            (fewest[i], prev[i]) = best;
+        }
+
+        // Levels after the last kept one are removed too
+        let (mut i, total) = (0..n)
+            .map(|i| (i, fewest[i] + (n - 1 - i)))
+            .min_by_key(|&(_, total)| total)?;
+        if total > k {
+            return None;
+        }
+        let mut removed: Vec<usize> = (i + 1..n).collect();
+        loop {
+            match prev[i] {
+                FIRST => {
+                    removed.extend(0..i);
+                    break;
+                }
+                j => {
+                    removed.extend(j + 1..i);
+                    i = j;
+                }
+            }
+        }
+        removed.sort_unstable();
+        Some(removed)
+    }
+
+    /// [`dampen`](SafetyPolicy::dampen) by trying every set of up to `k`
+    /// indices, smallest sets first. This is exponential in `k`, and only
+    /// used for policies with report rules and as the reference the fast
+    /// version is checked against.
+    pub fn dampen_brute_force(&self, xs: &[isize], k: usize) -> Option<Vec<usize>> {
+        let mut kept = Vec::with_capacity(xs.len());
+        for size in 0..=k.min(xs.len()) {
+            let mut removed: Vec<usize> = (0..size).collect();
+            loop {
+                kept.clear();
+                kept.extend(
+                    xs.iter()
+                        .enumerate()
+                        .filter(|(idx, _)| !removed.contains(idx))
+                        .map(|(_, x)| *x),
+                );
+                if self.is_safe(&kept) {
+                    return Some(removed);
+                }
+                if !next_combination(&mut removed, xs.len()) {
+                    break;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Advances `combination` to the next sorted set of as many indices below
+/// `n`, returning false after the last one.
+fn next_combination(combination: &mut [usize], n: usize) -> bool {
+    let size = combination.len();
+    let Some(pos) = (0..size)
+        .rev()
+        .find(|&pos| combination[pos] < n - size + pos)
+    else {
+        return false;
+    };
+    combination[pos] += 1;
+    for next in pos + 1..size {
+        combination[next] = combination[next - 1] + 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::Monotonicity;
+
+    #[test]
+    fn test_dampen() {
+        let policy = SafetyPolicy::default();
+        assert_eq!(policy.dampen(&[7, 6, 4, 2, 1], 1), Some(vec![]));
+        // Either of the out of place levels can go
+        let removed = policy.dampen(&[1, 3, 2, 4, 5], 1).unwrap();
+        assert!(removed == [1] || removed == [2]);
+        let removed = policy.dampen(&[8, 6, 4, 4, 1], 1).unwrap();
+        assert!(removed == [2] || removed == [3]);
+        assert_eq!(policy.dampen(&[1, 2, 7, 8, 9], 1), None);
+        assert_eq!(policy.dampen(&[9, 7, 6, 2, 1], 1), None);
+        assert_eq!(policy.dampen(&[1, 9, 9, 2, 3], 1), None);
+        assert_eq!(policy.dampen(&[1, 9, 9, 2, 3], 2), Some(vec![1, 2]));
+        assert_eq!(policy.dampen(&[40, 1, 2, 3, 50], 2), Some(vec![0, 4]));
+        assert_eq!(policy.dampen(&[5], 0), Some(vec![]));
+    }
+
+    #[test]
+    fn test_dampen_rules() {
+        let policy = SafetyPolicy::default().with_rule("not 5", |a, b| a != 5 && b != 5);
+        assert_eq!(policy.dampen(&[1, 2, 5, 6, 8], 1), None);
+        assert_eq!(policy.dampen(&[2, 3, 5, 6, 8], 1), Some(vec![2]));
+        assert_eq!(policy.dampen(&[5, 6, 8], 1), Some(vec![0]));
+
+        let policy =
+            SafetyPolicy::default().with_report_rule("at least 4 levels", |xs| xs.len() >= 4);
+        assert_eq!(policy.dampen(&[1, 2, 3, 9, 4], 1), Some(vec![3]));
+        assert_eq!(policy.dampen(&[1, 2, 9, 3], 1), None);
+        assert_eq!(
+            policy.dampen(&[1, 2, 9, 3], 1),
+            policy.dampen_brute_force(&[1, 2, 9, 3], 1)
+        );
+    }
+
+    #[test]
+    fn test_dampen_long_report() {
+        // Quadratic or worse in `k` would take far longer than linear here
+        let xs: Vec<isize> = (0..20_000)
+            .map(|x| if x % 100 == 50 { -x } else { x })
+            .collect();
+        let removed = SafetyPolicy::default().dampen(&xs, 1_000).unwrap();
+        assert_eq!(removed.len(), 200);
+    }
+
+    #[test]
+    fn test_next_combination() {
+        let mut combination = vec![0, 1];
+        let mut all = vec![combination.clone()];
+        while next_combination(&mut combination, 4) {
+            all.push(combination.clone());
+        }
+        assert_eq!(all.len(), 6);
+        assert_eq!(all.last(), Some(&vec![2, 3]));
+        assert!(!next_combination(&mut [], 3));
+    }
+
+    fn policies() -> impl Strategy<Value = SafetyPolicy> {
+        (
+            1isize..4,
+            0isize..5,
+            any::<bool>(),
+            any::<bool>(),
+            any::<bool>(),
+        )
+            .prop_map(|(min, extra, strict, rule, report_rule)| {
+                let monotonicity = if strict {
+                    Monotonicity::Strict
+                } else {
+                    Monotonicity::NonStrict
+                };
+                let policy = SafetyPolicy::default()
+                    .min_step(min)
+                    .max_step(min + extra)
+                    .monotonicity(monotonicity);
+                let policy = match rule {
+                    true => {
+                        policy.with_rule("no 7 after an even level", |a, b| a % 2 != 0 || b != 7)
+                    }
+                    false => policy,
+                };
+                match report_rule {
+                    true => policy.with_report_rule("at most one 3", |xs| {
+                        xs.iter().filter(|x| **x == 3).count() <= 1
+                    }),
+                    false => policy,
+                }
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(
+            policy in policies(),
+            xs in prop::collection::vec(0isize..12, 0..10),
+            k in 0usize..4,
+        ) {
+            let fast = policy.dampen(&xs, k);
+            let slow = policy.dampen_brute_force(&xs, k);
+            prop_assert_eq!(fast.as_ref().map(Vec::len), slow.as_ref().map(Vec::len));
+            if let Some(removed) = fast {
+                let kept: Vec<isize> = xs
+                    .iter()
+                    .enumerate()
+                    .filter(|(idx, _)| !removed.contains(idx))
+                    .map(|(_, x)| *x)
+                    .collect();
+                prop_assert!(policy.is_safe(&kept));
+            }
+        }
+    }
+}
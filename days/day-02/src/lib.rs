@@ -1,40 +1,68 @@
-pub fn is_safe(xs: &[isize]) -> bool {
-    (all_increasing(xs) || all_decreasing(xs)) && differing_adjacent(xs)
-}
+use std::fmt::Display;
 
-pub fn is_safe_tolerable(xs: &[isize]) -> bool {
-    (0..xs.len()).any(|idx| {
-        let ys = xs
-            .iter()
-            .copied()
-            .enumerate()
-            .flat_map(move |ix| if ix.0 == idx { None } else { Some(ix.1) })
-            .collect::<Vec<_>>();
-        is_safe(&ys)
-    })
+use advent2024::{InputSource, ParseError, Solution};
+
+mod batch;
+mod dampener;
+mod policy;
+mod report;
+mod rows;
+
+pub use batch::Reports;
+pub use policy::{Monotonicity, SafetyPolicy};
+pub use report::{analyze_report, Analysis, Violation};
+pub use rows::{FixedRows, Lane, StepsError};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+
+    type Input = Reports;
+
+    fn default_input() -> InputSource {
+        advent2024::default_input!()
+    }
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Reports::parse(input)
+    }
+
+    fn part1(reports: &Self::Input) -> impl Display {
+        reports.count_safe(&SafetyPolicy::default(), 0)
+    }
+
+    fn part2(reports: &Self::Input) -> impl Display {
+        reports.count_safe(&SafetyPolicy::default(), 1)
+    }
 }
 
-fn all_increasing(xs: &[isize]) -> bool {
-    xs.windows(2).all(|xs| xs[1] > xs[0])
+/// Whether `xs` is safe under the default [`SafetyPolicy`].
+pub fn is_safe(xs: &[isize]) -> bool {
+    SafetyPolicy::default().is_safe(xs)
 }
 
-fn all_decreasing(xs: &[isize]) -> bool {
-    xs.windows(2).all(|xs| xs[0] > xs[1])
+/// Whether `xs` is safe under the default [`SafetyPolicy`] once at most one
+/// level is removed.
+pub fn is_safe_tolerable(xs: &[isize]) -> bool {
+    SafetyPolicy::default().is_safe_tolerable(xs)
 }
 
-fn differing_adjacent(xs: &[isize]) -> bool {
-    xs.windows(2).all(|xs| {
-        let res = (xs[0] - xs[1]).abs();
-        (1..=3).contains(&res)
-    })
+pub fn try_parse_level(s: &str) -> Result<Vec<isize>, ParseError> {
+    s.split_ascii_whitespace()
+        .map(|x| {
+            x.parse::<isize>()
+                .map_err(|_| ParseError::new(s, x, "a level"))
+        })
+        .collect()
 }
 
 pub fn parse_level(s: &str) -> Vec<isize> {
-    s.split_ascii_whitespace()
-        .map(|x| x.parse::<isize>().unwrap())
-        .collect::<Vec<_>>()
+    try_parse_level(s).unwrap()
 }
 
+advent2024::example_tests!(Day02);
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -65,4 +93,11 @@ mod test {
         assert!(is_safe(&level));
         assert!(is_safe_tolerable(&level));
     }
+
+    #[test]
+    fn test_try_parse_level() {
+        assert_eq!(try_parse_level("7 6 4 2 1"), Ok(vec![7, 6, 4, 2, 1]));
+        let err = try_parse_level("7 6 four 2 1").unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (5, "four"));
+    }
 }
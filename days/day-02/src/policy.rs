@@ -0,0 +1,247 @@
+//! Configurable rules deciding whether a report is safe.
+
+use std::{fmt, ops::RangeInclusive, sync::Arc};
+
+/// Whether adjacent levels may be equal while still counting as increasing
+/// or decreasing. This alone decides equal levels, whatever the step range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Monotonicity {
+    Strict,
+    NonStrict,
+}
+
+/// A check on two adjacent levels, the earlier one first.
+type Rule = Arc<dyn Fn(isize, isize) -> bool + Send + Sync>;
+
+/// A check on a whole report.
+type ReportRule = Arc<dyn Fn(&[isize]) -> bool + Send + Sync>;
+
+/// What makes a report safe: every level moves in the same direction, by a
+/// step within `steps` or, when non-strict, not at all, every pair of
+/// adjacent levels passes every extra rule, and the report passes every
+/// report rule. The default policy is the puzzle's, strictly monotonic steps
+/// of 1 to 3.
+///
+/// Rules on adjacent levels keep [`dampen`](SafetyPolicy::dampen) linear,
+/// report rules make it try every removal.
+#[derive(Clone)]
+pub struct SafetyPolicy {
+    steps: RangeInclusive<isize>,
+    monotonicity: Monotonicity,
+    rules: Vec<(&'static str, Rule)>,
+    report_rules: Vec<(&'static str, ReportRule)>,
+}
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        SafetyPolicy {
+            steps: 1..=3,
+            monotonicity: Monotonicity::Strict,
+            rules: Vec::new(),
+            report_rules: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for SafetyPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules: Vec<_> = self.rules().collect();
+        let report_rules: Vec<_> = self.report_rules().collect();
+        f.debug_struct("SafetyPolicy")
+            .field("steps", &self.steps)
+            .field("monotonicity", &self.monotonicity)
+            .field("rules", &rules)
+            .field("report_rules", &report_rules)
+            .finish()
+    }
+}
+
+impl SafetyPolicy {
+    /// Smallest difference allowed between adjacent levels that differ.
+    ///
+    /// # Panics
+    ///
+    /// If `min` is below 1: equal levels are allowed by
+    /// [`Monotonicity::NonStrict`], not by the step range.
+    pub fn min_step(mut self, min: isize) -> Self {
+        assert!(
+            min >= 1,
+            "min step {min} is below 1, use Monotonicity::NonStrict to allow equal levels"
+        );
+        self.steps = min..=*self.steps.end();
+        self
+    }
+
+    /// Largest difference allowed between adjacent levels.
+    pub fn max_step(mut self, max: isize) -> Self {
+        self.steps = *self.steps.start()..=max;
+        self
+    }
+
+    pub fn monotonicity(mut self, monotonicity: Monotonicity) -> Self {
+        self.monotonicity = monotonicity;
+        self
+    }
+
+    /// Adds a rule every pair of adjacent levels in a safe report must also
+    /// pass, `name` identifying it when explaining why a report is unsafe.
+    pub fn with_rule(
+        mut self,
+        name: &'static str,
+        rule: impl Fn(isize, isize) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.rules.push((name, Arc::new(rule)));
+        self
+    }
+
+    /// Adds a rule every safe report must pass as a whole, such as a minimum
+    /// length, `name` identifying it when explaining why a report is unsafe.
+    pub fn with_report_rule(
+        mut self,
+        name: &'static str,
+        rule: impl Fn(&[isize]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.report_rules.push((name, Arc::new(rule)));
+        self
+    }
+
+    pub fn steps(&self) -> &RangeInclusive<isize> {
+        &self.steps
+    }
+
+    /// Whether equal adjacent levels make a report unsafe.
+    pub fn is_strict(&self) -> bool {
+        self.monotonicity == Monotonicity::Strict
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules.iter().map(|(name, _)| *name)
+    }
+
+    pub fn report_rules(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.report_rules.iter().map(|(name, _)| *name)
+    }
+
+    /// Whether going from `a` to `b` is an allowed step in the given
+    /// direction.
+    pub fn step_ok(&self, a: isize, b: isize, increasing: bool) -> bool {
+        let step = if increasing { b - a } else { a - b };
+        match step {
+            0 => self.monotonicity == Monotonicity::NonStrict,
+            step => self.steps.contains(&step),
+        }
+    }
+
+    /// Whether `b` may directly follow `a` in a report going in the given
+    /// direction: an allowed step that breaks no rule.
+    pub fn pair_ok(&self, a: isize, b: isize, increasing: bool) -> bool {
+        self.step_ok(a, b, increasing) && self.rules.iter().all(|(_, rule)| rule(a, b))
+    }
+
+    fn pairs_ok(&self, xs: &[isize], increasing: bool) -> bool {
+        xs.windows(2).all(|w| self.pair_ok(w[0], w[1], increasing))
+    }
+
+    /// The names of the extra rules the adjacent levels `a` and `b` break.
+    pub fn broken_rules(&self, a: isize, b: isize) -> impl Iterator<Item = &'static str> + '_ {
+        self.rules
+            .iter()
+            .filter(move |(_, rule)| !rule(a, b))
+            .map(|(name, _)| *name)
+    }
+
+    /// The names of the report rules `xs` breaks.
+    pub fn broken_report_rules<'a>(
+        &'a self,
+        xs: &'a [isize],
+    ) -> impl Iterator<Item = &'static str> + 'a {
+        self.report_rules
+            .iter()
+            .filter(|(_, rule)| !rule(xs))
+            .map(|(name, _)| *name)
+    }
+
+    pub fn is_safe(&self, xs: &[isize]) -> bool {
+        (self.pairs_ok(xs, true) || self.pairs_ok(xs, false))
+            && self.report_rules.iter().all(|(_, rule)| rule(xs))
+    }
+
+    /// Whether `xs` is safe once at most one level is removed.
+    pub fn is_safe_tolerable(&self, xs: &[isize]) -> bool {
+        self.dampen(xs, 1).is_some()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_steps() {
+        let policy = SafetyPolicy::default();
+        assert!(policy.is_safe(&[1, 3, 6, 7, 9]));
+        assert!(!policy.is_safe(&[8, 6, 4, 4, 1]));
+        assert!(!policy.is_safe(&[1, 2, 7, 8, 9]));
+
+        let policy = SafetyPolicy::default().max_step(5);
+        assert!(policy.is_safe(&[1, 2, 7, 8, 9]));
+        assert!(!SafetyPolicy::default().min_step(2).is_safe(&[1, 2, 4]));
+    }
+
+    #[test]
+    fn test_monotonicity_and_steps() {
+        let strict = SafetyPolicy::default();
+        let non_strict = strict.clone().monotonicity(Monotonicity::NonStrict);
+        // Equal levels depend only on the monotonicity
+        assert!(!strict.is_safe(&[8, 6, 4, 4, 1]));
+        assert!(non_strict.is_safe(&[8, 6, 4, 4, 1]));
+        assert!(!non_strict.is_safe(&[8, 6, 4, 5, 1]));
+        assert!(non_strict.is_safe(&[3, 3, 3]));
+
+        // Moving steps depend only on the range
+        let strict = strict.min_step(2);
+        let non_strict = non_strict.min_step(2);
+        assert!(!strict.is_safe(&[1, 3, 3, 5]));
+        assert!(non_strict.is_safe(&[1, 3, 3, 5]));
+        for policy in [&strict, &non_strict] {
+            assert!(policy.is_safe(&[1, 3, 5]));
+            assert!(!policy.is_safe(&[1, 2, 4]));
+            assert!(!policy.is_safe(&[1, 3, 7]));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "below 1")]
+    fn test_min_step_zero() {
+        let _ = SafetyPolicy::default().min_step(0);
+    }
+
+    #[test]
+    fn test_rules() {
+        let policy = SafetyPolicy::default()
+            .with_rule("no step of 2", |a, b| a.abs_diff(b) != 2)
+            .with_rule("positive", |a, b| a > 0 && b > 0);
+        assert!(policy.is_safe(&[1, 2, 3, 4]));
+        assert!(!policy.is_safe(&[1, 2, 4]));
+        assert!(!policy.is_safe(&[-1, 0, 1, 2]));
+        assert_eq!(policy.broken_rules(-1, 1).count(), 2);
+        assert_eq!(policy.broken_rules(1, 2).count(), 0);
+        assert!(format!("{policy:?}").contains("positive"));
+    }
+
+    #[test]
+    fn test_report_rules() {
+        let policy = SafetyPolicy::default()
+            .with_report_rule("positive", |xs| xs.iter().all(|x| *x > 0))
+            .with_report_rule("at least 4 levels", |xs| xs.len() >= 4);
+        assert!(policy.is_safe(&[1, 2, 3, 4]));
+        assert!(!policy.is_safe(&[1, 2, 3]));
+        assert!(!policy.is_safe(&[-1]));
+        assert_eq!(
+            policy.broken_report_rules(&[-1]).collect::<Vec<_>>(),
+            ["positive", "at least 4 levels"]
+        );
+        assert_eq!(policy.broken_report_rules(&[1, 2, 3, 4]).count(), 0);
+        assert!(format!("{policy:?}").contains("at least 4 levels"));
+    }
+}
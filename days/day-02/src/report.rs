@@ -0,0 +1,223 @@
+//! Explaining why a report is unsafe, and what would fix it.
+
+use std::fmt::{self, Display};
+
+use crate::SafetyPolicy;
+
+/// The first thing that makes a report unsafe. A window is a pair of adjacent
+/// levels, numbered by the index of its first level, and its delta is the
+/// second level minus the first. Displayed, levels are counted from 1 like
+/// the lines of the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// The levels turn around, going the other way than the first nonzero
+    /// delta did.
+    DirectionChange { window: usize, delta: isize },
+    /// The levels keep going the same way, but by a step out of range.
+    StepSize { window: usize, delta: isize },
+    /// The step is fine but one of the policy's extra rules fails.
+    Rule { window: usize, name: &'static str },
+    /// Every pair of levels is fine but one of the policy's report rules
+    /// fails.
+    ReportRule { name: &'static str },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::DirectionChange { window, delta } => write!(
+                f,
+                "direction changes between levels {} and {} ({delta:+})",
+                window + 1,
+                window + 2
+            ),
+            Violation::StepSize { window, delta } => write!(
+                f,
+                "step of {delta:+} between levels {} and {}",
+                window + 1,
+                window + 2
+            ),
+            Violation::Rule { window, name } => write!(
+                f,
+                "rule {name:?} broken between levels {} and {}",
+                window + 1,
+                window + 2
+            ),
+            Violation::ReportRule { name } => write!(f, "breaks rule {name:?}"),
+        }
+    }
+}
+
+/// Verdict on one report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// `None` if the report is safe as is.
+    pub violation: Option<Violation>,
+    /// How many levels could be removed to make it safe.
+    pub tolerance: usize,
+    /// The fewest level indices to remove, at most `tolerance`, to make the
+    /// report safe, `None` if that is not enough. Displayed counting from 1,
+    /// as in [`Violation`].
+    pub fix: Option<Vec<usize>>,
+}
+
+impl Analysis {
+    pub fn is_safe(&self) -> bool {
+        self.violation.is_none()
+    }
+
+    pub fn is_fixable(&self) -> bool {
+        self.fix.is_some()
+    }
+}
+
+impl Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(violation) = &self.violation else {
+            return write!(f, "safe");
+        };
+        write!(f, "unsafe, {violation}")?;
+        match &self.fix {
+            _ if self.tolerance == 0 => Ok(()),
+            Some(fix) => {
+                let levels: Vec<usize> = fix.iter().map(|idx| idx + 1).collect();
+                write!(f, "; removing levels {levels:?} fixes it")
+            }
+            None => write!(
+                f,
+                "; not fixed by removing {} or fewer levels",
+                self.tolerance
+            ),
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// The first violation in `xs`, if any, and which removals of up to
+    /// `tolerance` levels fix it.
+    pub fn analyze_report(&self, xs: &[isize], tolerance: usize) -> Analysis {
+        let violation = self.first_violation(xs);
+        let fix = match violation {
+            None => Some(Vec::new()),
+            Some(_) if tolerance == 0 => None,
+            Some(_) => self.dampen(xs, tolerance),
+        };
+        Analysis {
+            violation,
+            tolerance,
+            fix,
+        }
+    }
+
+    fn first_violation(&self, xs: &[isize]) -> Option<Violation> {
+        let deltas = xs.windows(2).map(|w| w[1] - w[0]);
+        // A report of equal levels is judged as increasing, either way fails
+        let increasing = deltas.clone().find(|d| *d != 0).is_none_or(|d| d > 0);
+        for (window, delta) in deltas.enumerate() {
+            if delta != 0 && (delta > 0) != increasing {
+                return Some(Violation::DirectionChange { window, delta });
+            }
+            if !self.step_ok(xs[window], xs[window + 1], increasing) {
+                return Some(Violation::StepSize { window, delta });
+            }
+            if let Some(name) = self.broken_rules(xs[window], xs[window + 1]).next() {
+                return Some(Violation::Rule { window, name });
+            }
+        }
+        self.broken_report_rules(xs)
+            .next()
+            .map(|name| Violation::ReportRule { name })
+    }
+}
+
+/// [`SafetyPolicy::analyze_report`] under the default policy, tolerating one
+/// bad level as the Problem Dampener does.
+pub fn analyze_report(xs: &[isize]) -> Analysis {
+    SafetyPolicy::default().analyze_report(xs, 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_analyze_report() {
+        let analysis = analyze_report(&[7, 6, 4, 2, 1]);
+        assert!(analysis.is_safe());
+        assert_eq!(analysis.to_string(), "safe");
+
+        let analysis = analyze_report(&[1, 2, 7, 8, 9]);
+        assert_eq!(
+            analysis.violation,
+            Some(Violation::StepSize {
+                window: 1,
+                delta: 5
+            })
+        );
+        assert!(!analysis.is_fixable());
+
+        let analysis = analyze_report(&[1, 3, 2, 4, 5]);
+        assert_eq!(
+            analysis.violation,
+            Some(Violation::DirectionChange {
+                window: 1,
+                delta: -1
+            })
+        );
+        assert!(analysis.is_fixable());
+
+        let analysis = analyze_report(&[1, 9, 3, 4, 5]);
+        assert_eq!(
+            analysis.to_string(),
+            "unsafe, step of +8 between levels 1 and 2; removing levels [2] fixes it"
+        );
+        let analysis = analyze_report(&[1, 9, 9, 4, 5]);
+        assert_eq!(
+            analysis.to_string(),
+            "unsafe, step of +8 between levels 1 and 2; not fixed by removing 1 or fewer levels"
+        );
+
+        let analysis = SafetyPolicy::default().analyze_report(&[9, 7, 6, 2, 1], 0);
+        assert_eq!(
+            analysis.to_string(),
+            "unsafe, step of -4 between levels 3 and 4"
+        );
+    }
+
+    #[test]
+    fn test_agrees_with_is_safe() {
+        let policy = SafetyPolicy::default().with_rule("below 80", |a, b| a < 80 && b < 80);
+        for xs in include_str!("../extra/input")
+            .lines()
+            .map(crate::parse_level)
+        {
+            let analysis = policy.analyze_report(&xs, 1);
+            assert_eq!(analysis.is_safe(), policy.is_safe(&xs));
+            assert_eq!(analysis.is_fixable(), policy.is_safe_tolerable(&xs));
+        }
+        let analysis = policy.analyze_report(&[77, 78, 80, 81], 1);
+        assert_eq!(
+            analysis.violation,
+            Some(Violation::Rule {
+                window: 1,
+                name: "below 80"
+            })
+        );
+        assert_eq!(
+            analysis.to_string(),
+            "unsafe, rule \"below 80\" broken between levels 2 and 3; \
+             not fixed by removing 1 or fewer levels"
+        );
+
+        let policy = SafetyPolicy::default().with_report_rule("short", |xs| xs.len() < 5);
+        let analysis = policy.analyze_report(&[1, 2, 3, 4, 5], 1);
+        assert_eq!(
+            analysis.violation,
+            Some(Violation::ReportRule { name: "short" })
+        );
+        assert_eq!(
+            analysis.to_string(),
+            "unsafe, breaks rule \"short\"; removing levels [1] fixes it"
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Reports as fixed-width rows of narrow integers, checked without branches.
+//!
+//! Every row holds `W` levels, the tail past the report's length being
+//! padding. The checks always walk all `W - 1` deltas and mask out the
+//! padded ones instead of stopping early, so that the compiler can unroll
+//! and vectorize them.
+
+use std::{fmt, ops::RangeInclusive};
+
+use crate::{Reports, SafetyPolicy};
+
+/// Narrow integer types levels can be stored as.
+pub trait Lane: Copy + Default + TryFrom<isize> {
+    fn widen(self) -> i32;
+}
+
+impl Lane for i8 {
+    fn widen(self) -> i32 {
+        self as i32
+    }
+}
+
+impl Lane for i16 {
+    fn widen(self) -> i32 {
+        self as i32
+    }
+}
+
+/// A policy whose steps the rows cannot check.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepsError {
+    /// A bound does not fit the `i32` deltas between lanes are taken in.
+    TooWide(RangeInclusive<isize>),
+}
+
+impl fmt::Display for StepsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepsError::TooWide(steps) => write!(f, "step range {steps:?} does not fit in i32"),
+        }
+    }
+}
+
+impl std::error::Error for StepsError {}
+
+/// The checked form of a policy's steps.
+#[derive(Debug, Clone, Copy)]
+struct Steps {
+    min: i32,
+    max: i32,
+    /// Whether any nonzero delta is allowed, false for an empty range.
+    moving: bool,
+    /// Whether a delta of 0 is allowed, as the range never includes it.
+    flat: bool,
+}
+
+impl Steps {
+    fn new(policy: &SafetyPolicy) -> Result<Self, StepsError> {
+        let steps = policy.steps();
+        let flat = !policy.is_strict();
+        if steps.is_empty() {
+            return Ok(Steps {
+                min: 0,
+                max: 0,
+                moving: false,
+                flat,
+            });
+        }
+        let fit =
+            |bound: isize| i32::try_from(bound).map_err(|_| StepsError::TooWide(steps.clone()));
+        Ok(Steps {
+            min: fit(*steps.start())?,
+            max: fit(*steps.end())?,
+            moving: true,
+            flat,
+        })
+    }
+}
+
+/// Reports of at most `W` levels, each level fitting in `T`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedRows<T, const W: usize> {
+    rows: Vec<[T; W]>,
+    lens: Vec<u8>,
+}
+
+impl<T: Lane, const W: usize> FixedRows<T, W> {
+    /// `None` if a report is longer than `W` levels or has a level that does
+    /// not fit in `T`.
+    pub fn from_reports(reports: &Reports) -> Option<Self> {
+        let mut rows = Vec::with_capacity(reports.len());
+        let mut lens = Vec::with_capacity(reports.len());
+        for levels in reports.iter() {
+            if levels.len() > W || levels.len() > u8::MAX as usize {
+                return None;
+            }
+            let mut row = [T::default(); W];
+            for (lane, level) in row.iter_mut().zip(levels) {
+                *lane = T::try_from(*level).ok()?;
+            }
+            rows.push(row);
+            lens.push(levels.len() as u8);
+        }
+        Some(FixedRows { rows, lens })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Whether each row is safe under `policy`, as
+    /// [`SafetyPolicy::is_safe`] would say. A policy with extra rules or
+    /// report rules has its rows checked by `is_safe` itself, without the
+    /// branch-free fast path.
+    pub fn safe(&self, policy: &SafetyPolicy) -> Result<Vec<bool>, StepsError> {
+        let check = checker(policy)?;
+        Ok(self
+            .rows
+            .iter()
+            .zip(&self.lens)
+            .map(|(row, len)| check(row, *len as usize))
+            .collect())
+    }
+
+    pub fn count_safe(&self, policy: &SafetyPolicy) -> Result<usize, StepsError> {
+        let check = checker(policy)?;
+        Ok(self
+            .rows
+            .iter()
+            .zip(&self.lens)
+            .map(|(row, len)| check(row, *len as usize) as usize)
+            .sum())
+    }
+}
+
+/// The check of one row of `len` levels under `policy`.
+fn checker<T: Lane, const W: usize>(
+    policy: &SafetyPolicy,
+) -> Result<impl Fn(&[T; W], usize) -> bool + '_, StepsError> {
+    let steps = Steps::new(policy)?;
+    let rules = policy.rules().chain(policy.report_rules()).next().is_some();
+    Ok(move |row: &[T; W], len: usize| {
+        if !rules {
+            return row_safe(row, len, steps);
+        }
+        let levels: Vec<isize> = row[..len].iter().map(|x| x.widen() as isize).collect();
+        policy.is_safe(&levels)
+    })
+}
+
+fn row_safe<T: Lane, const W: usize>(row: &[T; W], len: usize, steps: Steps) -> bool {
+    // `min <= d && d <= max` as a single unsigned comparison, which holds for
+    // any `min <= max`
+    let Steps {
+        min,
+        max,
+        moving,
+        flat,
+    } = steps;
+    let span = max.wrapping_sub(min) as u32;
+    let mut increasing = true;
+    let mut decreasing = true;
+    for idx in 0..W.saturating_sub(1) {
+        let delta = row[idx + 1].widen() - row[idx].widen();
+        let allowed = (idx + 1 >= len) | (flat & (delta == 0));
+        increasing &= allowed | (moving & (delta.wrapping_sub(min) as u32 <= span));
+        decreasing &= allowed | (moving & ((-delta).wrapping_sub(min) as u32 <= span));
+    }
+    increasing | decreasing
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{is_safe, Monotonicity};
+
+    #[test]
+    fn test_fixed_rows() {
+        let reports = Reports::parse(include_str!("../extra/input")).unwrap();
+        let expected: Vec<bool> = reports.iter().map(is_safe).collect();
+        let policy = SafetyPolicy::default();
+
+        let narrow = FixedRows::<i8, 8>::from_reports(&reports).unwrap();
+        assert_eq!(narrow.len(), reports.len());
+        assert_eq!(narrow.safe(&policy), Ok(expected.clone()));
+        let wide = FixedRows::<i16, 16>::from_reports(&reports).unwrap();
+        assert_eq!(wide.safe(&policy), Ok(expected.clone()));
+        assert_eq!(
+            wide.count_safe(&policy),
+            Ok(expected.iter().filter(|s| **s).count())
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let reports = Reports::parse("1 2 3 4 5\n-128 127\n").unwrap();
+        assert_eq!(FixedRows::<i8, 4>::from_reports(&reports), None);
+        let rows = FixedRows::<i8, 5>::from_reports(&reports).unwrap();
+        let policy = SafetyPolicy::default();
+        assert_eq!(rows.safe(&policy), Ok(vec![true, false]));
+        assert_eq!(
+            rows.safe(&policy.clone().max_step(255)),
+            Ok(vec![true, true])
+        );
+        let reports = Reports::parse("1 200\n").unwrap();
+        assert_eq!(FixedRows::<i8, 2>::from_reports(&reports), None);
+        assert!(FixedRows::<i16, 2>::from_reports(&reports).is_some());
+
+        // Short and empty reports are safe, as with `is_safe`
+        let reports: Reports = [&[4][..], &[]].into_iter().collect();
+        let rows = FixedRows::<i8, 8>::from_reports(&reports).unwrap();
+        assert_eq!(rows.safe(&policy), Ok(vec![true, true]));
+    }
+
+    #[test]
+    fn test_policies() {
+        let reports = Reports::parse("1 2 2 3\n1 3 5\n9 6 3\n").unwrap();
+        let rows = FixedRows::<i8, 4>::from_reports(&reports).unwrap();
+        let non_strict = SafetyPolicy::default().monotonicity(Monotonicity::NonStrict);
+        assert_eq!(rows.safe(&non_strict), Ok(vec![true, true, true]));
+        let rule = SafetyPolicy::default().with_rule("no 5", |a, b| a != 5 && b != 5);
+        assert_eq!(rows.safe(&rule), Ok(vec![false, false, true]));
+        let long = non_strict.with_report_rule("at least 4 levels", |xs| xs.len() >= 4);
+        assert_eq!(rows.safe(&long), Ok(vec![true, false, false]));
+
+        // With no step allowed, only flat reports are safe, and only when
+        // non-strict
+        let reports = Reports::parse("3 3 3\n1 2\n7\n").unwrap();
+        let flat = FixedRows::<i8, 4>::from_reports(&reports).unwrap();
+        let empty = SafetyPolicy::default().max_step(0);
+        assert_eq!(flat.safe(&empty), Ok(vec![false, false, true]));
+        let empty = empty.monotonicity(Monotonicity::NonStrict);
+        assert_eq!(flat.safe(&empty), Ok(vec![true, false, true]));
+        assert!(empty.is_safe(&[3, 3, 3]));
+        let wide = SafetyPolicy::default().max_step(isize::MAX);
+        assert!(matches!(
+            rows.count_safe(&wide),
+            Err(StepsError::TooWide(_))
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_is_safe(
+            levels in prop::collection::vec(prop::collection::vec(-20isize..20, 0..8), 1..20),
+            min in 1isize..4,
+            extra in -2isize..5,
+            strict in any::<bool>(),
+        ) {
+            let monotonicity = if strict { Monotonicity::Strict } else { Monotonicity::NonStrict };
+            let policy = SafetyPolicy::default()
+                .min_step(min)
+                .max_step(min + extra)
+                .monotonicity(monotonicity);
+            let reports: Reports = levels.iter().map(Vec::as_slice).collect();
+            let rows = FixedRows::<i8, 8>::from_reports(&reports).unwrap();
+            let expected: Vec<bool> = reports.iter().map(|l| policy.is_safe(l)).collect();
+            prop_assert_eq!(rows.safe(&policy), Ok(expected));
+        }
+    }
+}
@@ -1,10 +1,16 @@
-use std::io::Read;
+use std::process::ExitCode;
 
-use day_03::uncorrupted;
+use advent2024::Part;
+use day_03::{Day03, InstructionSet};
 
-fn main() {
-    let mut reader = advent2024::open_file_cmd_line();
-    let mut buf = String::new();
-    reader.read_to_string(&mut buf).unwrap();
-    println!("{}", uncorrupted(&buf));
-}
\ No newline at end of file
+fn main() -> ExitCode {
+    advent2024::run_part_streaming::<Day03, _>(
+        Part::One,
+        "--highlight",
+        |input, _, _| {
+            print!("{}", InstructionSet::part1().highlight(input));
+            Ok(())
+        },
+        |source| day_03::solve_stream(Part::One, source),
+    )
+}
@@ -1,130 +1,197 @@
-use nom::{
-    branch::alt,
-    bytes::complete::tag,
-    character::complete::{anychar, u32},
-    multi::fold_many0,
-    sequence::{delimited, separated_pair},
-    IResult,
+use std::fmt::Display;
+
+use advent2024::{Error, InputSource, ParseError, Part, Solution};
+
+mod machine;
+mod scan;
+mod stream;
+mod tokens;
+
+pub use machine::{
+    Arithmetic, Call, Exec, InstructionSet, Machine, Opcode, Overflow, State, Step, Width,
 };
+pub use scan::Scanner;
+pub use stream::{run_reader, CallReader, RunError, MAX_CARRY};
+pub use tokens::{Token, TokenKind};
 
-#[derive(Debug, PartialEq, Eq)]
-struct Mul(u32, u32);
+/// Every instruction recovered from the corrupted memory, in order.
+#[derive(Debug)]
+pub struct Program(Vec<Call>);
 
-#[derive(Debug, PartialEq, Eq)]
-enum Instruction {
-    Mul(Mul),
-    Do,
-    Dont,
-    Junk,
+impl Program {
+    pub fn calls(&self) -> &[Call] {
+        &self.0
+    }
 }
 
-fn parse_mul(input: &str) -> IResult<&str, Instruction> {
-    let inner = separated_pair(u32, tag(","), u32);
-    let (rest, (x, y)) = delimited(tag("mul("), inner, tag(")"))(input)?;
-    Ok((rest, Instruction::Mul(Mul(x, y))))
-}
+pub struct Day03;
 
-fn parse_junk(input: &str) -> IResult<&str, Instruction> {
-    let (rest, _) = anychar(input)?;
-    Ok((rest, Instruction::Junk))
-}
+impl Solution for Day03 {
+    const DAY: u8 = 3;
 
-fn parse_do(input: &str) -> IResult<&str, Instruction> {
-    let (rest, _) = tag("do()")(input)?;
-    Ok((rest, Instruction::Do))
-}
+    type Input = Program;
+
+    fn default_input() -> InputSource {
+        advent2024::default_input!()
+    }
 
-fn parse_dont(input: &str) -> IResult<&str, Instruction> {
-    let (rest, _) = tag("don't()")(input)?;
-    Ok((rest, Instruction::Dont))
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Program(calls(&InstructionSet::part2(), input)))
+    }
+
+    fn part1(program: &Self::Input) -> impl Display {
+        solve(&InstructionSet::part1(), program)
+    }
+
+    fn part2(program: &Self::Input) -> impl Display {
+        solve(&InstructionSet::part2(), program)
+    }
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
-    alt((parse_mul, parse_dont, parse_do, parse_junk))(input)
+/// With operands of at most three digits, a `u128` would only wrap after
+/// more than 10^32 calls, so the answers never need to report an overflow.
+fn solve(set: &InstructionSet, program: &Program) -> u128 {
+    Machine::new(set, &program.0)
+        .arithmetic(Arithmetic::Wrapping(Width::U128))
+        .run()
+        .expect("wrapping arithmetic never overflows")
 }
 
-fn parse_full_instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
-    fold_many0(parse_instruction, Vec::new, |mut acc: Vec<_>, item| {
-        acc.push(item);
-        acc
-    })(input)
+/// The answer to `part`, running each call as it is read from `source`
+/// instead of reading the whole input first.
+pub fn solve_stream(part: Part, source: &InputSource) -> Result<u128, Error> {
+    let set = match part {
+        Part::One => InstructionSet::part1(),
+        Part::Two => InstructionSet::part2(),
+    };
+    let arithmetic = Arithmetic::Wrapping(Width::U128);
+    run_reader(&set, source.open()?, arithmetic).map_err(|e| match e {
+        RunError::Io(e) => Error::Io {
+            path: source.to_string().into(),
+            source: e,
+        },
+        RunError::Overflow(_) => unreachable!("wrapping arithmetic never overflows"),
+    })
 }
 
-fn parse_instructions(input: &str) -> IResult<&str, Vec<Mul>> {
-    fold_many0(parse_instruction, Vec::new, |mut acc: Vec<_>, item| {
-        if let Instruction::Mul(mul) = item {
-            acc.push(mul);
-        }
-        acc
-    })(input)
+/// The calls in `input`, found by the scanner with the `scanner` feature and
+/// by the nom parser without.
+fn calls(set: &InstructionSet, input: &str) -> Vec<Call> {
+    #[cfg(feature = "scanner")]
+    let calls = set.scan(input);
+    #[cfg(not(feature = "scanner"))]
+    let calls = set.parse(input);
+    calls
 }
 
-fn multiply(xs: &[Mul]) -> u32 {
-    xs.iter().fold(0, |acc, m| acc + (m.0 * m.1))
+/// Runs every call in `input` with a checked `u64` accumulator.
+fn run(set: &InstructionSet, input: &str) -> Result<u64, Overflow> {
+    let acc = Machine::new(set, &calls(set, input))
+        .arithmetic(Arithmetic::Checked(Width::U64))
+        .run()?;
+    Ok(acc as u64)
 }
 
-pub fn uncorrupted(input: &str) -> u32 {
-    let (_, xs) = parse_instructions(input).unwrap();
-    multiply(&xs)
+/// Sum of the products of every `mul` in `input`, or the `mul` that took it
+/// past `u64::MAX`.
+pub fn uncorrupted(input: &str) -> Result<u64, Overflow> {
+    run(&InstructionSet::part1(), input)
 }
-pub fn uncorrupted_enabled(input: &str) -> u32 {
-    let (_, res) = parse_full_instructions(input).unwrap();
-    let mut enabled = true;
-    let mut acc = 0;
-    for cmd in res.into_iter() {
-        match cmd {
-            Instruction::Mul(mul) if enabled => acc += mul.0 * mul.1,
-            Instruction::Do => {
-                enabled = true;
-            }
-            Instruction::Dont => {
-                enabled = false;
-            }
-            _ => continue,
-        }
-    }
-    acc
+
+/// [`uncorrupted`], only counting the `mul`s enabled by `do()` and
+/// `don't()`.
+pub fn uncorrupted_enabled(input: &str) -> Result<u64, Overflow> {
+    run(&InstructionSet::part2(), input)
 }
 
+advent2024::example_tests!(Day03);
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    fn call(name: &'static str, args: &[u32]) -> Call {
+        Call {
+            name,
+            args: args.to_vec(),
+        }
+    }
+
     #[test]
     fn test_parse_mul() {
         let s = "mul(10,20)";
-        assert_eq!(parse_mul(s), Ok(("", Instruction::Mul(Mul(10, 20)))));
+        assert_eq!(
+            InstructionSet::part1().parse(s),
+            vec![call("mul", &[10, 20])]
+        );
     }
 
     #[test]
     fn test_uncorrupted() {
-        let s = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        let s = include_str!("../extra/example1.txt");
         assert_eq!(
-            parse_instructions(s).unwrap().1,
-            vec![Mul(2, 4), Mul(5, 5), Mul(11, 8), Mul(8, 5)]
+            InstructionSet::part1().parse(s),
+            vec![
+                call("mul", &[2, 4]),
+                call("mul", &[5, 5]),
+                call("mul", &[11, 8]),
+                call("mul", &[8, 5])
+            ]
         );
-        assert_eq!(uncorrupted(s), 161);
+        assert_eq!(uncorrupted(s), Ok(161));
     }
 
     #[test]
     fn test_parse_full_instructions() {
-        let s = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let s = include_str!("../extra/example2.txt");
         assert_eq!(
-            parse_full_instructions(s)
-                .unwrap()
-                .1
-                .into_iter()
-                .filter(|x| !matches!(x, Instruction::Junk))
-                .collect::<Vec<_>>(),
+            Day03::parse(s).unwrap().calls(),
             vec![
-                Instruction::Mul(Mul(2, 4)),
-                Instruction::Dont,
-                Instruction::Mul(Mul(5, 5)),
-                Instruction::Mul(Mul(11, 8)),
-                Instruction::Do,
-                Instruction::Mul(Mul(8, 5))
+                call("mul", &[2, 4]),
+                call("don't", &[]),
+                call("mul", &[5, 5]),
+                call("mul", &[11, 8]),
+                call("do", &[]),
+                call("mul", &[8, 5])
             ]
         );
-        assert_eq!(uncorrupted_enabled(s), 48);
+        assert_eq!(uncorrupted_enabled(s), Ok(48));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Past `u32::MAX`, where the sum used to wrap
+        let s = "mul(999,999)".repeat(5000);
+        assert_eq!(uncorrupted(&s), Ok(999 * 999 * 5000));
+        assert_eq!(uncorrupted_enabled(&format!("don't(){s}")), Ok(0));
+
+        let set = InstructionSet::part1().digits(1..=10);
+        let err = run(
+            &set,
+            "mul(1,1)mul(4294967295,4294967295)mul(4294967295,4294967295)",
+        )
+        .unwrap_err();
+        assert_eq!((err.ip, err.width), (2, Width::U64));
+        assert_eq!(err.call.args, [u32::MAX, u32::MAX]);
+    }
+
+    #[test]
+    fn test_solve_stream() {
+        let input = include_str!("../extra/input");
+        let program = Day03::parse(input).unwrap();
+        let source = InputSource::Path(concat!(env!("CARGO_MANIFEST_DIR"), "/extra/input").into());
+        for part in Part::ALL {
+            assert_eq!(
+                solve_stream(part, &source).unwrap().to_string(),
+                Day03::solve(&program, part)
+            );
+        }
+        let source = InputSource::Embedded(include_str!("../extra/example2.txt"));
+        assert_eq!(solve_stream(Part::Two, &source).unwrap(), 48);
+        let missing = InputSource::Path("does/not/exist".into());
+        assert!(matches!(
+            solve_stream(Part::One, &missing),
+            Err(Error::Io { .. })
+        ));
     }
 }
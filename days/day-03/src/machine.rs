@@ -0,0 +1,483 @@
+//! An interpreter for the instructions hidden in the corrupted memory.
+//!
+//! An [`InstructionSet`] is a table of opcodes, each a name, how many
+//! arguments it takes and what it does to the [`State`]. The same table
+//! tells the parser which `name(arg,...)` calls to recognize, so adding an
+//! opcode is a matter of registering it with [`InstructionSet::with`].
+//!
+//! The accumulator is a `u128` whose [`Arithmetic`] decides how wide it
+//! really is and what happens when it would overflow: wrap around, or stop
+//! the machine with an [`Overflow`] naming the instruction responsible.
+
+use std::{fmt, ops::RangeInclusive};
+
+/// What an opcode does, given its arguments.
+pub type Exec = fn(&mut State, &[u32]);
+
+#[derive(Debug, Clone, Copy)]
+pub struct Opcode {
+    pub name: &'static str,
+    pub arity: usize,
+    pub exec: Exec,
+}
+
+/// A recognized call of one of the opcodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub name: &'static str,
+    pub args: Vec<u32>,
+}
+
+impl fmt::Display for Call {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (idx, arg) in self.args.iter().enumerate() {
+            if idx > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{arg}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// How many bits of the accumulator are used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    U32,
+    U64,
+    U128,
+}
+
+impl Width {
+    pub fn max(self) -> u128 {
+        match self {
+            Width::U32 => u32::MAX as u128,
+            Width::U64 => u64::MAX as u128,
+            Width::U128 => u128::MAX,
+        }
+    }
+}
+
+impl fmt::Display for Width {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Width::U32 => write!(f, "u32"),
+            Width::U64 => write!(f, "u64"),
+            Width::U128 => write!(f, "u128"),
+        }
+    }
+}
+
+/// What the accumulator does when a result does not fit its width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arithmetic {
+    /// Keep the low bits, like the unsigned integer types in release builds.
+    Wrapping(Width),
+    /// Leave the accumulator as it was and report an [`Overflow`].
+    Checked(Width),
+}
+
+impl Arithmetic {
+    pub fn width(self) -> Width {
+        match self {
+            Arithmetic::Wrapping(width) | Arithmetic::Checked(width) => width,
+        }
+    }
+}
+
+impl Default for Arithmetic {
+    fn default() -> Self {
+        Arithmetic::Checked(Width::U64)
+    }
+}
+
+/// An instruction whose result did not fit in the accumulator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    /// Index of the instruction in the program.
+    pub ip: usize,
+    pub call: Call,
+    pub width: Width,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "instruction {} `{}` overflowed the {} accumulator",
+            self.ip, self.call, self.width
+        )
+    }
+}
+
+impl std::error::Error for Overflow {}
+
+/// Everything the instructions can change.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    /// Whether `mul` currently counts.
+    pub enabled: bool,
+    /// Only ever changed through [`State::add`] and [`State::sub`], which
+    /// respect `arithmetic`.
+    pub acc: u128,
+    pub arithmetic: Arithmetic,
+    /// Set by a checked operation that did not fit, until the machine
+    /// reports it.
+    pub overflowed: bool,
+    /// Saved `enabled` flags, for opcodes opening and closing nested scopes.
+    pub scopes: Vec<bool>,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::new(Arithmetic::default())
+    }
+}
+
+impl State {
+    pub fn new(arithmetic: Arithmetic) -> Self {
+        State {
+            enabled: true,
+            acc: 0,
+            arithmetic,
+            overflowed: false,
+            scopes: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, value: u128) {
+        let checked = self.acc.checked_add(value);
+        self.apply(checked, self.acc.wrapping_add(value));
+    }
+
+    pub fn sub(&mut self, value: u128) {
+        let checked = self.acc.checked_sub(value);
+        self.apply(checked, self.acc.wrapping_sub(value));
+    }
+
+    /// Stores the result of an operation, given as the exact value (`None`
+    /// past `u128`) and the value wrapped at 128 bits.
+    fn apply(&mut self, checked: Option<u128>, wrapped: u128) {
+        match self.arithmetic {
+            Arithmetic::Wrapping(width) => self.acc = wrapped & width.max(),
+            Arithmetic::Checked(width) => match checked.filter(|v| *v <= width.max()) {
+                Some(value) => self.acc = value,
+                None => self.overflowed = true,
+            },
+        }
+    }
+}
+
+fn mul(state: &mut State, args: &[u32]) {
+    if state.enabled {
+        state.add(args[0] as u128 * args[1] as u128);
+    }
+}
+
+fn enable(state: &mut State, _: &[u32]) {
+    state.enabled = true;
+}
+
+fn disable(state: &mut State, _: &[u32]) {
+    state.enabled = false;
+}
+
+#[derive(Debug, Clone)]
+pub struct InstructionSet {
+    opcodes: Vec<Opcode>,
+    digits: RangeInclusive<usize>,
+}
+
+/// No opcodes, and arguments of up to the ten digits of `u32::MAX`.
+impl Default for InstructionSet {
+    fn default() -> Self {
+        InstructionSet {
+            opcodes: Vec::new(),
+            digits: 1..=10,
+        }
+    }
+}
+
+impl InstructionSet {
+    /// Registers `name`, replacing any opcode of the same name.
+    pub fn with(mut self, name: &'static str, arity: usize, exec: Exec) -> Self {
+        self.opcodes.retain(|op| op.name != name);
+        self.opcodes.push(Opcode { name, arity, exec });
+        self
+    }
+
+    /// How many digits an argument may have, leading zeros included. Calls
+    /// with longer or shorter arguments are junk.
+    pub fn digits(mut self, digits: RangeInclusive<usize>) -> Self {
+        self.digits = digits;
+        self
+    }
+
+    pub fn allows_digits(&self, count: usize) -> bool {
+        self.digits.contains(&count)
+    }
+
+    /// Longest a call of `op` can be, every argument having as many digits
+    /// as allowed.
+    pub fn max_len(&self, op: &Opcode) -> usize {
+        let args = op.arity.saturating_mul(*self.digits.end());
+        (op.name.len() + 2 + op.arity.saturating_sub(1)).saturating_add(args)
+    }
+
+    /// Only `mul(a,b)`, with arguments of one to three digits.
+    pub fn part1() -> Self {
+        InstructionSet::default().digits(1..=3).with("mul", 2, mul)
+    }
+
+    /// `mul(a,b)`, switched off by `don't()` and back on by `do()`.
+    pub fn part2() -> Self {
+        Self::part1()
+            .with("do", 0, enable)
+            .with("don't", 0, disable)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Opcode> {
+        self.opcodes.iter().find(|op| op.name == name)
+    }
+
+    pub fn opcodes(&self) -> &[Opcode] {
+        &self.opcodes
+    }
+
+    /// Runs `call` if its opcode is in the set and it has as many arguments
+    /// as the opcode takes, returning whether it was run.
+    pub fn exec(&self, state: &mut State, call: &Call) -> bool {
+        match self.get(call.name) {
+            Some(op) if op.arity == call.args.len() => {
+                (op.exec)(state, &call.args);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// One executed instruction and the state it left behind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub ip: usize,
+    pub name: &'static str,
+    /// False when the opcode is not in the machine's instruction set or the
+    /// call has the wrong number of arguments for it.
+    pub executed: bool,
+    pub enabled: bool,
+    pub acc: u128,
+}
+
+/// Runs `program` against `set`, skipping calls to opcodes `set` lacks and
+/// calls with the wrong number of arguments.
+#[derive(Debug, Clone)]
+pub struct Machine<'a> {
+    set: &'a InstructionSet,
+    program: &'a [Call],
+    state: State,
+    ip: usize,
+    trace: Option<Vec<Step>>,
+}
+
+impl<'a> Machine<'a> {
+    pub fn new(set: &'a InstructionSet, program: &'a [Call]) -> Self {
+        Machine {
+            set,
+            program,
+            state: State::default(),
+            ip: 0,
+            trace: None,
+        }
+    }
+
+    /// Replaces the default checked `u64` accumulator.
+    pub fn arithmetic(mut self, arithmetic: Arithmetic) -> Self {
+        self.state.arithmetic = arithmetic;
+        self
+    }
+
+    /// Records a [`Step`] for every instruction from now on.
+    pub fn tracing(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Index of the next instruction to run.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn trace(&self) -> &[Step] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.ip >= self.program.len()
+    }
+
+    /// Runs the next instruction, returning false once there are none left.
+    ///
+    /// An instruction that overflows leaves the state and `ip` where they
+    /// were, so the machine can be inspected after the error.
+    pub fn step(&mut self) -> Result<bool, Overflow> {
+        let Some(call) = self.program.get(self.ip) else {
+            return Ok(false);
+        };
+        let executed = self.set.exec(&mut self.state, call);
+        if std::mem::take(&mut self.state.overflowed) {
+            return Err(Overflow {
+                ip: self.ip,
+                call: call.clone(),
+                width: self.state.arithmetic.width(),
+            });
+        }
+        if let Some(trace) = &mut self.trace {
+            trace.push(Step {
+                ip: self.ip,
+                name: call.name,
+                executed,
+                enabled: self.state.enabled,
+                acc: self.state.acc,
+            });
+        }
+        self.ip += 1;
+        Ok(true)
+    }
+
+    /// Runs every remaining instruction, returning the accumulator.
+    pub fn run(&mut self) -> Result<u128, Overflow> {
+        while self.step()? {}
+        Ok(self.state.acc)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_machine() {
+        let input = include_str!("../extra/example2.txt");
+        let program = InstructionSet::part2().parse(input);
+        assert_eq!(
+            Machine::new(&InstructionSet::part2(), &program).run(),
+            Ok(48)
+        );
+        assert_eq!(
+            Machine::new(&InstructionSet::part1(), &program).run(),
+            Ok(161)
+        );
+
+        let set = InstructionSet::part2();
+        let mut machine = Machine::new(&set, &program).tracing();
+        assert_eq!(machine.step(), Ok(true));
+        assert_eq!((machine.ip(), machine.state().acc), (1, 8));
+        machine.run().unwrap();
+        assert!(machine.is_halted() && machine.step() == Ok(false));
+        let trace = machine.trace();
+        assert_eq!(trace.len(), program.len());
+        assert!(!trace[2].enabled && trace[2].acc == 8);
+    }
+
+    #[test]
+    fn test_extensions() {
+        fn add(state: &mut State, args: &[u32]) {
+            state.add((args[0] + args[1]) as u128);
+        }
+        fn sub(state: &mut State, args: &[u32]) {
+            state.sub(args[0] as u128);
+        }
+        fn open(state: &mut State, _: &[u32]) {
+            state.scopes.push(state.enabled);
+        }
+        fn close(state: &mut State, _: &[u32]) {
+            state.enabled = state.scopes.pop().unwrap_or(true);
+        }
+        let set = InstructionSet::part2()
+            .with("add", 2, add)
+            .with("sub", 1, sub)
+            .with("begin", 0, open)
+            .with("end", 0, close);
+        let input = "add(1,2)sub(1)begin()don't()mul(5,5)end()mul(2,3)";
+        let program = set.parse(input);
+        assert_eq!(program.len(), 7);
+        assert_eq!(Machine::new(&set, &program).run(), Ok(3 - 1 + 6));
+
+        let program = set.parse("add(1,1)sub(3)mul(2,2)");
+        let err = Machine::new(&set, &program).run().unwrap_err();
+        assert_eq!((err.ip, err.call.to_string()), (1, "sub(3)".to_string()));
+        let wrapped = Machine::new(&set, &program)
+            .arithmetic(Arithmetic::Wrapping(Width::U32))
+            .run();
+        assert_eq!(wrapped, Ok(3));
+    }
+
+    #[test]
+    fn test_arity() {
+        let program = [
+            Call {
+                name: "mul",
+                args: vec![1],
+            },
+            Call {
+                name: "mul",
+                args: vec![2, 3],
+            },
+            Call {
+                name: "do",
+                args: vec![4],
+            },
+        ];
+        let set = InstructionSet::part2();
+        let mut machine = Machine::new(&set, &program).tracing();
+        assert_eq!(machine.run(), Ok(6));
+        let executed: Vec<_> = machine.trace().iter().map(|step| step.executed).collect();
+        assert_eq!(executed, [false, true, false]);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let set = InstructionSet::part1().digits(1..=10);
+        let program = set.parse("mul(4294967295,4294967295)mul(4294967295,2)");
+        let run = |arithmetic| Machine::new(&set, &program).arithmetic(arithmetic).run();
+        let square = u32::MAX as u128 * u32::MAX as u128;
+
+        assert_eq!(
+            run(Arithmetic::Checked(Width::U64)),
+            Ok(square + 2 * u32::MAX as u128)
+        );
+        assert_eq!(
+            run(Arithmetic::Checked(Width::U128)),
+            run(Arithmetic::Checked(Width::U64))
+        );
+        let err = run(Arithmetic::Checked(Width::U32)).unwrap_err();
+        assert_eq!(err.ip, 0);
+        assert_eq!(
+            err.to_string(),
+            "instruction 0 `mul(4294967295,4294967295)` overflowed the u32 accumulator"
+        );
+        assert_eq!(
+            run(Arithmetic::Wrapping(Width::U32)),
+            Ok((square + 2 * u32::MAX as u128) & u32::MAX as u128)
+        );
+
+        // The failing instruction is left for inspection
+        let mut machine = Machine::new(&set, &program).arithmetic(Arithmetic::Checked(Width::U32));
+        assert!(machine.step().is_err());
+        assert_eq!((machine.ip(), machine.state().acc), (0, 0));
+
+        let mut state = State::new(Arithmetic::Checked(Width::U128));
+        state.add(u128::MAX);
+        state.add(1);
+        assert!(state.overflowed && state.acc == u128::MAX);
+        let mut state = State::new(Arithmetic::Wrapping(Width::U128));
+        state.sub(1);
+        assert_eq!(state.acc, u128::MAX);
+    }
+}
@@ -0,0 +1,266 @@
+//! A hand-rolled scanner finding the same calls as [`InstructionSet::parse`]
+//! without trying the nom parsers at every byte.
+//!
+//! `memchr` jumps straight to the next byte an opcode name can start with,
+//! and from there the call is read by a small state machine over bytes, so
+//! junk costs little more than a search for one to three bytes.
+
+use std::ops::Range;
+
+use memchr::{memchr, memchr2, memchr3};
+
+use crate::{Call, InstructionSet, Opcode};
+
+/// The bytes opcode names start with, searched for with the fastest tool
+/// that handles that many.
+#[derive(Debug, Clone)]
+enum Starts {
+    One(u8),
+    Two(u8, u8),
+    Three(u8, u8, u8),
+    Table(Box<[bool; 256]>),
+    /// An empty name matches at every character.
+    Anywhere,
+}
+
+impl Starts {
+    fn new(set: &InstructionSet) -> Self {
+        let mut firsts: Vec<u8> = Vec::new();
+        for op in set.opcodes() {
+            match op.name.as_bytes().first() {
+                Some(byte) if !firsts.contains(byte) => firsts.push(*byte),
+                Some(_) => {}
+                None => return Starts::Anywhere,
+            }
+        }
+        match firsts[..] {
+            [a] => Starts::One(a),
+            [a, b] => Starts::Two(a, b),
+            [a, b, c] => Starts::Three(a, b, c),
+            _ => {
+                let mut table = Box::new([false; 256]);
+                firsts.iter().for_each(|b| table[*b as usize] = true);
+                Starts::Table(table)
+            }
+        }
+    }
+
+    /// First position at or after `from` where a call could start. Names
+    /// start with an ASCII or leading byte, so this is a character boundary.
+    fn find(&self, haystack: &[u8], from: usize) -> Option<usize> {
+        let rest = &haystack[from..];
+        let found = match self {
+            Starts::One(a) => memchr(*a, rest),
+            Starts::Two(a, b) => memchr2(*a, *b, rest),
+            Starts::Three(a, b, c) => memchr3(*a, *b, *c, rest),
+            Starts::Table(table) => rest.iter().position(|b| table[*b as usize]),
+            Starts::Anywhere => rest.iter().position(|b| b & 0xc0 != 0x80),
+        };
+        found.map(|idx| from + idx)
+    }
+}
+
+/// Where the argument list is up to.
+#[derive(Debug, Clone, Copy)]
+enum Args {
+    /// Just after `(`: an argument or `)`.
+    Open,
+    /// Just after `,`: an argument.
+    Comma,
+    /// Inside an argument of `count` digits, `None` once past `u32::MAX`.
+    Digits { count: usize, value: Option<u32> },
+}
+
+/// Iterator over the calls in `input` and their byte ranges, in the order
+/// [`InstructionSet::tokenize`] finds them.
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    set: &'a InstructionSet,
+    input: &'a str,
+    starts: Starts,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(set: &'a InstructionSet, input: &'a str) -> Self {
+        Scanner {
+            set,
+            input,
+            starts: Starts::new(set),
+            pos: 0,
+        }
+    }
+
+    /// The call of `op` starting at `start`, and where it ends.
+    fn call(&self, op: &Opcode, start: usize) -> Option<(usize, Call)> {
+        let bytes = self.input.as_bytes();
+        let mut idx = start + op.name.len();
+        if !bytes[start..].starts_with(op.name.as_bytes()) || bytes.get(idx) != Some(&b'(') {
+            return None;
+        }
+        idx += 1;
+
+        let mut args = Vec::with_capacity(op.arity);
+        let mut state = Args::Open;
+        loop {
+            let byte = *bytes.get(idx)?;
+            idx += 1;
+            state = match (state, byte) {
+                (Args::Open | Args::Comma, b'0'..=b'9') => Args::Digits {
+                    count: 1,
+                    value: Some((byte - b'0') as u32),
+                },
+                (Args::Digits { count, value }, b'0'..=b'9') => Args::Digits {
+                    count: count + 1,
+                    value: value
+                        .and_then(|v| v.checked_mul(10))
+                        .and_then(|v| v.checked_add((byte - b'0') as u32)),
+                },
+                (Args::Open, b')') => break,
+                (Args::Digits { count, value }, b',' | b')') => {
+                    if !self.set.allows_digits(count) || args.len() == op.arity {
+                        return None;
+                    }
+                    args.push(value?);
+                    if byte == b')' {
+                        break;
+                    }
+                    Args::Comma
+                }
+                _ => return None,
+            };
+        }
+        let call = Call {
+            name: op.name,
+            args,
+        };
+        (call.args.len() == op.arity).then_some((idx, call))
+    }
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = (Range<usize>, Call);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.starts.find(self.input.as_bytes(), self.pos)?;
+            let found = self
+                .set
+                .opcodes()
+                .iter()
+                .find_map(|op| self.call(op, start));
+            match found {
+                Some((end, call)) => {
+                    self.pos = end;
+                    return Some((start..end, call));
+                }
+                None => self.pos = start + 1,
+            }
+        }
+    }
+}
+
+impl InstructionSet {
+    pub fn scanner<'a>(&'a self, input: &'a str) -> Scanner<'a> {
+        Scanner::new(self, input)
+    }
+
+    /// The calls [`InstructionSet::parse`] finds, found faster.
+    pub fn scan(&self, input: &str) -> Vec<Call> {
+        self.scanner(input).map(|(_, call)| call).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::{State, TokenKind};
+
+    /// The calls and ranges found by the nom tokenizer.
+    fn tokenized(set: &InstructionSet, input: &str) -> Vec<(Range<usize>, Call)> {
+        set.tokenize(input)
+            .into_iter()
+            .filter_map(|token| match token.kind {
+                TokenKind::Call(call) => Some((token.range, call)),
+                TokenKind::Junk => None,
+            })
+            .collect()
+    }
+
+    fn nop(_: &mut State, _: &[u32]) {}
+
+    /// Names sharing prefixes and first bytes, one of them not ASCII, and
+    /// enough first bytes to need the lookup table.
+    fn crowded() -> InstructionSet {
+        InstructionSet::part2()
+            .digits(2..=4)
+            .with("mu", 1, nop)
+            .with("x", 3, nop)
+            .with("été", 0, nop)
+            .with("a", 1, nop)
+    }
+
+    #[test]
+    fn test_examples() {
+        for input in [
+            include_str!("../extra/example1.txt"),
+            include_str!("../extra/example2.txt"),
+            include_str!("../extra/input"),
+        ] {
+            for set in [InstructionSet::part1(), InstructionSet::part2(), crowded()] {
+                assert_eq!(
+                    set.scanner(input).collect::<Vec<_>>(),
+                    tokenized(&set, input)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let set = InstructionSet::part1();
+        for input in [
+            "mul(1,)mul(,1)mul()mul(1,2,3)mul(1234,5)mul(1,2",
+            "mmul(2,3)mumul(4,5)mul(6,7))",
+            "mul(4294967296,1)mul(0,000)",
+        ] {
+            assert_eq!(
+                set.scanner(input).collect::<Vec<_>>(),
+                tokenized(&set, input)
+            );
+        }
+        assert_eq!(set.scan("mmul(2,3)mumul(4,5)").len(), 2);
+
+        let wide = InstructionSet::part1().digits(1..=12);
+        let input = "mul(4294967295,1)mul(4294967296,1)mul(000004294967295,1)";
+        assert_eq!(wide.scan(input), wide.parse(input));
+        assert_eq!(InstructionSet::default().scan("mul(1,2)"), []);
+        let anywhere = InstructionSet::default().with("", 0, nop);
+        assert_eq!(anywhere.scan("é()x"), anywhere.parse("é()x"));
+    }
+
+    proptest! {
+        #[test]
+        fn scanner_matches_nom(input in "([mudon't()x,é]|[0-9]{1,5}|mul\\(|don't\\(\\)|do\\(\\)|.){0,40}") {
+            for set in [InstructionSet::part2(), crowded()] {
+                prop_assert_eq!(set.scanner(&input).collect::<Vec<_>>(), tokenized(&set, &input));
+            }
+        }
+
+        #[test]
+        fn scanner_matches_nom_on_calls(
+            calls in prop::collection::vec(("mul|mu|do|don't|x|a|été", prop::collection::vec(0u64..20_000_000_000, 0..4), "[ ,()a-z]{0,2}"), 0..20),
+        ) {
+            let mut input = String::new();
+            for (name, args, junk) in calls {
+                let args: Vec<_> = args.iter().map(u64::to_string).collect();
+                input.push_str(&format!("{name}({}){junk}", args.join(",")));
+            }
+            for set in [InstructionSet::part2(), crowded(), InstructionSet::part2().digits(1..=11)] {
+                prop_assert_eq!(set.scanner(&input).collect::<Vec<_>>(), tokenized(&set, &input));
+            }
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Recovering calls from a reader a chunk at a time, for inputs too large to
+//! read whole.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    io::{self, BufRead},
+    str,
+};
+
+use crate::{Arithmetic, Call, InstructionSet, Overflow, State, TokenKind};
+
+/// Iterator over the calls [`InstructionSet::parse`] would find in
+/// everything `reader` yields, holding on to one chunk of it at a time.
+///
+/// A call is never longer than [`InstructionSet::max_len`], so after
+/// scanning a chunk everything found before the last `max_len` bytes is
+/// final. The rest is carried over and scanned again with the next chunk,
+/// which is how calls split across chunks are still found. Instruction sets
+/// whose calls can be longer than [`MAX_CARRY`] are refused, so that memory
+/// stays bounded by the chunk size plus `MAX_CARRY`.
+pub struct CallReader<'a, R> {
+    set: &'a InstructionSet,
+    reader: R,
+    max_len: usize,
+    buf: Vec<u8>,
+    calls: VecDeque<Call>,
+    done: bool,
+}
+
+/// Most bytes a [`CallReader`] carries over from one chunk to the next.
+pub const MAX_CARRY: usize = 64 * 1024;
+
+impl<'a, R: BufRead> CallReader<'a, R> {
+    /// Fails with [`io::ErrorKind::InvalidInput`] if a call of `set` can be
+    /// longer than [`MAX_CARRY`], as allowing very long arguments with
+    /// [`InstructionSet::digits`] does.
+    pub fn new(set: &'a InstructionSet, reader: R) -> io::Result<Self> {
+        let max_len = set.opcodes().iter().map(|op| set.max_len(op)).max();
+        let max_len = max_len.unwrap_or(0);
+        if max_len > MAX_CARRY {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("calls can be {max_len} bytes long, more than the {MAX_CARRY} carried between chunks"),
+            ));
+        }
+        Ok(CallReader {
+            set,
+            reader,
+            max_len,
+            buf: Vec::new(),
+            calls: VecDeque::new(),
+            done: false,
+        })
+    }
+
+    /// Reads one more chunk, queueing the calls it completes.
+    fn fill(&mut self) -> io::Result<()> {
+        let chunk = self.reader.fill_buf()?;
+        let read = chunk.len();
+        self.done = read == 0;
+        self.buf.extend_from_slice(chunk);
+        self.reader.consume(read);
+
+        // A character split across chunks waits for the rest of its bytes
+        let valid = match str::from_utf8(&self.buf) {
+            Ok(text) => text.len(),
+            Err(e) if e.error_len().is_none() && !self.done => e.valid_up_to(),
+            Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e)),
+        };
+        let text = str::from_utf8(&self.buf[..valid]).expect("checked above");
+
+        // Scanning at `safe` or later could depend on bytes not read yet
+        let safe = if self.done {
+            text.len()
+        } else {
+            text.len().saturating_sub(self.max_len)
+        };
+        let mut cut = safe;
+        for token in self.set.tokenize(text) {
+            if token.range.start >= safe {
+                break;
+            }
+            if let TokenKind::Call(call) = token.kind {
+                cut = cut.max(token.range.end);
+                self.calls.push_back(call);
+            }
+        }
+        while !text.is_char_boundary(cut) {
+            cut += 1;
+        }
+        self.buf.drain(..cut);
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for CallReader<'_, R> {
+    type Item = io::Result<Call>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.calls.is_empty() && !self.done {
+            if let Err(e) = self.fill() {
+                self.done = true;
+                return Some(Err(e));
+            }
+        }
+        self.calls.pop_front().map(Ok)
+    }
+}
+
+#[derive(Debug)]
+pub enum RunError {
+    Io(io::Error),
+    Overflow(Overflow),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Io(e) => write!(f, "failed to read program: {e}"),
+            RunError::Overflow(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for RunError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RunError::Io(e) => Some(e),
+            RunError::Overflow(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for RunError {
+    fn from(e: io::Error) -> Self {
+        RunError::Io(e)
+    }
+}
+
+impl From<Overflow> for RunError {
+    fn from(e: Overflow) -> Self {
+        RunError::Overflow(e)
+    }
+}
+
+/// Runs every call in `reader` as it is read, returning the accumulator.
+/// Instructions are numbered from 0 in the order they are read, as in
+/// [`Machine`](crate::Machine).
+pub fn run_reader<R: BufRead>(
+    set: &InstructionSet,
+    reader: R,
+    arithmetic: Arithmetic,
+) -> Result<u128, RunError> {
+    let mut state = State::new(arithmetic);
+    for (ip, call) in CallReader::new(set, reader)?.enumerate() {
+        let call = call?;
+        set.exec(&mut state, &call);
+        if state.overflowed {
+            let width = arithmetic.width();
+            return Err(Overflow { ip, call, width }.into());
+        }
+    }
+    Ok(state.acc)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+    use crate::Width;
+
+    fn calls<R: BufRead>(set: &InstructionSet, reader: R) -> Vec<Call> {
+        CallReader::new(set, reader)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn test_every_split() {
+        let set = InstructionSet::part2();
+        for input in [
+            include_str!("../extra/example1.txt"),
+            include_str!("../extra/example2.txt"),
+        ] {
+            let expected = set.parse(input);
+            let bytes = input.as_bytes();
+            for split in 0..=bytes.len() {
+                let (a, b) = bytes.split_at(split);
+                assert_eq!(calls(&set, a.chain(b)), expected, "split at {split}");
+            }
+            for capacity in 1..8 {
+                let reader = BufReader::with_capacity(capacity, bytes);
+                assert_eq!(calls(&set, reader), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_characters() {
+        let set = InstructionSet::part1();
+        let input = "→mul(999,1)é€mul(2,3)mul(1234,5)";
+        for capacity in 1..4 {
+            let reader = BufReader::with_capacity(capacity, input.as_bytes());
+            assert_eq!(calls(&set, reader), set.parse(input));
+        }
+        let invalid = CallReader::new(&set, &b"mul(1,2)\xff"[..]).unwrap().last();
+        assert!(matches!(invalid, Some(Err(e)) if e.kind() == io::ErrorKind::InvalidData));
+        let truncated = CallReader::new(&set, &b"mul(1,2)\xe2\x82"[..])
+            .unwrap()
+            .last();
+        assert!(matches!(truncated, Some(Err(_))));
+    }
+
+    #[test]
+    fn test_bounded_carry() {
+        let set = InstructionSet::part2().digits(1..=10);
+        let input = format!(
+            "{}mul(1,2){}do()",
+            "mul(".repeat(50_000),
+            "x".repeat(100_000)
+        );
+        let bound = set
+            .opcodes()
+            .iter()
+            .map(|op| set.max_len(op))
+            .max()
+            .unwrap()
+            + 64
+            + 4;
+        let mut reader =
+            CallReader::new(&set, BufReader::with_capacity(64, input.as_bytes())).unwrap();
+        let mut calls = Vec::new();
+        while !reader.done {
+            reader.fill().unwrap();
+            assert!(
+                reader.buf.len() <= bound,
+                "carried {} bytes",
+                reader.buf.len()
+            );
+            calls.extend(reader.calls.drain(..));
+        }
+        assert_eq!(calls, set.parse(&input));
+
+        for digits in [1..=usize::MAX, 1..=MAX_CARRY] {
+            let set = InstructionSet::part1().digits(digits);
+            let err = CallReader::new(&set, &b"mul(1,2)"[..]).err().unwrap();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+            let err = run_reader(&set, &b"mul(1,2)"[..], Arithmetic::default());
+            assert!(matches!(err, Err(RunError::Io(_))));
+        }
+        assert!(CallReader::new(&InstructionSet::part1().digits(1..=1000), &b""[..]).is_ok());
+    }
+
+    #[test]
+    fn test_run_reader() {
+        let input = include_str!("../extra/input");
+        let reader = BufReader::with_capacity(64, input.as_bytes());
+        let acc = run_reader(&InstructionSet::part2(), reader, Arithmetic::default());
+        assert_eq!(
+            acc.unwrap(),
+            crate::uncorrupted_enabled(input).unwrap() as u128
+        );
+
+        let set = InstructionSet::part1().digits(1..=10);
+        let input = "mul(1,2)xmul(4294967295,4294967295)";
+        let err = run_reader(&set, input.as_bytes(), Arithmetic::Checked(Width::U32));
+        assert!(matches!(
+            err,
+            Err(RunError::Overflow(Overflow { ip: 1, .. }))
+        ));
+        let err = run_reader(&set, &b"mul(1,2)\xff"[..], Arithmetic::default());
+        assert!(matches!(err, Err(RunError::Io(_))));
+    }
+}
@@ -0,0 +1,201 @@
+//! Splitting the corrupted memory into calls and runs of junk, keeping the
+//! byte range each came from.
+
+use std::ops::Range;
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{anychar, char, digit1},
+    error::{Error, ErrorKind},
+    multi::separated_list0,
+    sequence::delimited,
+    IResult,
+};
+use nom_locate::LocatedSpan;
+
+use crate::{Call, InstructionSet};
+
+type Span<'a> = LocatedSpan<&'a str>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Call(Call),
+    /// Everything between two calls, however long.
+    Junk,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    /// Byte range of the token in the input.
+    pub range: Range<usize>,
+    pub kind: TokenKind,
+}
+
+/// ANSI colors of the opcodes, in the order they were registered.
+const PALETTE: [&str; 6] = ["32", "36", "35", "33", "34", "31"];
+
+impl InstructionSet {
+    /// An argument with an allowed number of digits that fits in a `u32`.
+    fn operand<'a>(&self, input: Span<'a>) -> IResult<Span<'a>, u32> {
+        let (rest, digits) = digit1(input)?;
+        let fail = |kind| nom::Err::Error(Error::new(input, kind));
+        if !self.allows_digits(digits.len()) {
+            return Err(fail(ErrorKind::Verify));
+        }
+        let value = digits.parse().map_err(|_| fail(ErrorKind::Digit))?;
+        Ok((rest, value))
+    }
+
+    fn parse_call<'a>(&self, input: Span<'a>) -> Option<(Span<'a>, Call)> {
+        self.opcodes().iter().find_map(|op| {
+            let operand = |i| self.operand(i);
+            let mut args = delimited(char('('), separated_list0(char(','), operand), char(')'));
+            let parsed: IResult<_, _> = tag(op.name)(input).and_then(|(rest, _)| args(rest));
+            let (rest, args) = parsed.ok()?;
+            let call = Call {
+                name: op.name,
+                args,
+            };
+            (call.args.len() == op.arity).then_some((rest, call))
+        })
+    }
+
+    /// Every call of a known opcode in `input` and the junk between them.
+    pub fn tokenize(&self, input: &str) -> Vec<Token> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut span = Span::new(input);
+        while !span.is_empty() {
+            let start = span.location_offset();
+            if let Some((rest, call)) = self.parse_call(span) {
+                tokens.push(Token {
+                    range: start..rest.location_offset(),
+                    kind: TokenKind::Call(call),
+                });
+                span = rest;
+                continue;
+            }
+            let (rest, _): (_, char) = anychar::<_, ()>(span).expect("span is not empty");
+            let end = rest.location_offset();
+            match tokens.last_mut() {
+                Some(Token {
+                    range,
+                    kind: TokenKind::Junk,
+                }) => range.end = end,
+                _ => tokens.push(Token {
+                    range: start..end,
+                    kind: TokenKind::Junk,
+                }),
+            }
+            span = rest;
+        }
+        tokens
+    }
+
+    /// Every call of a known opcode in `input`, skipping everything else.
+    pub fn parse(&self, input: &str) -> Vec<Call> {
+        self.tokenize(input)
+            .into_iter()
+            .filter_map(|token| match token.kind {
+                TokenKind::Call(call) => Some(call),
+                TokenKind::Junk => None,
+            })
+            .collect()
+    }
+
+    /// `input` with each call colored by its opcode for an ANSI terminal.
+    pub fn highlight(&self, input: &str) -> String {
+        let mut out = String::with_capacity(input.len() * 2);
+        for token in self.tokenize(input) {
+            let text = &input[token.range];
+            match token.kind {
+                TokenKind::Call(call) => {
+                    let idx = self.opcodes().iter().position(|op| op.name == call.name);
+                    let color = PALETTE[idx.unwrap_or(0) % PALETTE.len()];
+                    out.push_str(&format!("\x1b[1;{color}m{text}\x1b[0m"));
+                }
+                TokenKind::Junk => out.push_str(text),
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let calls = InstructionSet::part2().parse("xdo()mul(2,4)don't(mul(1,2,3)mul(3,4)do(1)");
+        let names: Vec<_> = calls.iter().map(|c| c.name).collect();
+        assert_eq!(names, ["do", "mul", "mul"]);
+        assert_eq!(calls[2].args, [3, 4]);
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let input = include_str!("../extra/example2.txt");
+        let tokens = InstructionSet::part2().tokenize(input);
+        let ranges: Vec<_> = tokens
+            .iter()
+            .filter(|t| matches!(t.kind, TokenKind::Call(_)))
+            .map(|t| &input[t.range.clone()])
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                "mul(2,4)",
+                "don't()",
+                "mul(5,5)",
+                "mul(11,8)",
+                "do()",
+                "mul(8,5)"
+            ]
+        );
+        assert_eq!(tokens[0].range, 0..1);
+        assert_eq!(
+            tokens[2],
+            Token {
+                range: 9..20,
+                kind: TokenKind::Junk
+            }
+        );
+        // Tokens cover the input without gaps, and junk never follows junk
+        assert!(tokens.windows(2).all(|w| w[0].range.end == w[1].range.start
+            && !(w[0].kind == TokenKind::Junk && w[1].kind == TokenKind::Junk)));
+        assert_eq!(tokens.last().unwrap().range.end, input.len());
+    }
+
+    #[test]
+    fn test_digits() {
+        let set = InstructionSet::part1();
+        assert_eq!(set.parse("mul(123,4)mul(1234,5)mul(,6)mul(007,8)").len(), 2);
+
+        let wide = InstructionSet::part1().digits(1..=10);
+        assert_eq!(wide.parse("mul(4294967295,0000000001)").len(), 1);
+        assert!(wide.parse("mul(00000000001,2)").is_empty());
+        assert!(wide.parse("mul(4294967296,2)").is_empty());
+        let padded = InstructionSet::part1().digits(2..=20);
+        assert_eq!(
+            padded.parse("mul(00000000000000000001,02)mul(1,22)").len(),
+            1
+        );
+        assert_eq!(padded.max_len(padded.get("mul").unwrap()), 46);
+    }
+
+    #[test]
+    fn test_multibyte_junk() {
+        let tokens = InstructionSet::part1().tokenize("é→mul(1,2)");
+        assert_eq!(tokens[0].range, 0..5);
+        assert_eq!(tokens[1].range, 5..13);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let set = InstructionSet::part2();
+        assert_eq!(
+            set.highlight("x do()mul(2,3)"),
+            "x \x1b[1;36mdo()\x1b[0m\x1b[1;32mmul(2,3)\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    advent2024::examples::generate();
+}
@@ -1,11 +1,7 @@
-use std::io::Read;
+use std::process::ExitCode;
 
-use advent2024::open_file_cmd_line;
+use advent2024::Part;
 
-fn main() {
-    let mut file = open_file_cmd_line();
-    let mut buf = String::new();
-    file.read_to_string(&mut buf).unwrap();
-    let puzzle = day_04::Puzzle::from_iter(buf.split_whitespace());
-    println!("{}", puzzle.count_x_mas());
-}
\ No newline at end of file
+fn main() -> ExitCode {
+    advent2024::run_part::<day_04::Day04>(Part::Two)
+}
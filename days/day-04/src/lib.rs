@@ -1,7 +1,40 @@
-use std::iter::repeat;
+use std::{fmt::Display, iter::repeat};
 
+use advent2024::{InputSource, ParseError, Solution};
 use itertools::Itertools;
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+
+    type Input = Puzzle;
+
+    fn default_input() -> InputSource {
+        advent2024::default_input!()
+    }
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut rows = input.split_whitespace();
+        let width = rows
+            .next()
+            .ok_or_else(|| ParseError::end_of_line(input, "a row of letters"))?
+            .len();
+        if let Some(row) = rows.find(|row| row.len() != width) {
+            return Err(ParseError::new(input, row, "rows of equal length"));
+        }
+        Ok(Puzzle::from_iter(input.split_whitespace()))
+    }
+
+    fn part1(puzzle: &Self::Input) -> impl Display {
+        puzzle.count_xmas()
+    }
+
+    fn part2(puzzle: &Self::Input) -> impl Display {
+        puzzle.count_x_mas()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 struct WordSearch {
     row: usize,
@@ -169,6 +202,8 @@ impl Puzzle {
     }
 }
 
+advent2024::example_tests!(Day04);
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -225,16 +260,7 @@ SAMX";
 
     #[test]
     fn test_part0x() {
-        let letters = "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX";
+        let letters = include_str!("../extra/example.txt");
         let puzzle = Puzzle::from_iter(letters.split_whitespace());
         assert_eq!(puzzle.count_xmas(), 18);
         assert_eq!(puzzle.count_x_mas(), 9);
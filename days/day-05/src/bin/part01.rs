@@ -1,9 +1,7 @@
-use std::io::BufRead;
+use std::process::ExitCode;
 
-use day_05::count_middle_pages;
+use advent2024::Part;
 
-fn main() {
-    let file = advent2024::open_file_cmd_line();
-    let iter = file.lines().map_while(Result::ok);
-    println!("{}", count_middle_pages(iter));
-}
\ No newline at end of file
+fn main() -> ExitCode {
+    advent2024::run_part::<day_05::Day05>(Part::One)
+}
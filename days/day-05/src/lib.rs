@@ -1,4 +1,33 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use advent2024::{InputSource, ParseError, Solution};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+
+    type Input = (PageOrdering, Vec<PageNumbers>);
+
+    fn default_input() -> InputSource {
+        advent2024::default_input!()
+    }
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        try_parse_rules_pages(input)
+    }
+
+    fn part1((porder, pnums): &Self::Input) -> impl Display {
+        middle_pages(porder, pnums)
+    }
+
+    fn part2((porder, pnums): &Self::Input) -> impl Display {
+        wrong_middle_pages(porder, pnums)
+    }
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct PageOrdering {
@@ -6,11 +35,15 @@ pub struct PageOrdering {
 }
 
 impl PageOrdering {
-    fn add_rule<S: AsRef<str>>(&mut self, s: S) {
-        let mut parsed = s.as_ref().split(['|', '\n']);
-        let fst = parsed.next().unwrap().parse::<usize>().unwrap();
-        let snd = parsed.next().unwrap().parse::<usize>().unwrap();
+    fn try_add_rule<S: AsRef<str>>(&mut self, s: S) -> Result<(), ParseError> {
+        let line = s.as_ref().trim_end_matches(['\r', '\n']);
+        let (fst, snd) = line
+            .split_once('|')
+            .ok_or_else(|| ParseError::new(line, line, "a rule `X|Y`"))?;
+        let fst = parse_page(line, fst)?;
+        let snd = parse_page(line, snd)?;
         self.befores.entry(fst).or_default().insert(snd);
+        Ok(())
     }
 
     pub fn valid(&self, page_num: usize, ahead: &HashSet<usize>) -> bool {
@@ -51,13 +84,17 @@ impl PageNumbers {
         })
     }
 
-    pub fn parse_page_numbers<S: AsRef<str> + ?Sized>(s: &S) -> Self {
-        let pnumbers = s
-            .as_ref()
+    pub fn try_parse_page_numbers<S: AsRef<str> + ?Sized>(s: &S) -> Result<Self, ParseError> {
+        let line = s.as_ref();
+        let pnumbers = line
             .split(',')
-            .map(|x| x.parse::<usize>().unwrap())
-            .collect::<Vec<_>>();
-        Self(pnumbers)
+            .map(|x| parse_page(line, x))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self(pnumbers))
+    }
+
+    pub fn parse_page_numbers<S: AsRef<str> + ?Sized>(s: &S) -> Self {
+        Self::try_parse_page_numbers(s).unwrap()
     }
 
     pub fn reorder_pages(&self, page_order: &PageOrdering) -> Self {
@@ -72,100 +109,84 @@ impl PageNumbers {
     }
 }
 
-pub fn parse_rules_pages(s: &str) -> (PageOrdering, Vec<PageNumbers>) {
-    let mut porder = PageOrdering::default();
-    let mut lines = s.lines();
-    let mut next_line = lines.next();
-    while !next_line.unwrap().is_empty() {
-        porder.add_rule(next_line.unwrap());
-        next_line = lines.next();
-    }
-    let pnums = lines.map(PageNumbers::parse_page_numbers).collect();
-    (porder, pnums)
+fn parse_page(line: &str, token: &str) -> Result<usize, ParseError> {
+    token
+        .parse()
+        .map_err(|_| ParseError::new(line, token, "a page number"))
 }
 
-pub fn count_wrong_middle_pages<I: Iterator<Item = S>, S: AsRef<str>>(mut iter: I) -> usize {
+pub fn try_parse_rules_pages(s: &str) -> Result<(PageOrdering, Vec<PageNumbers>), ParseError> {
     let mut porder = PageOrdering::default();
-    let mut binding = iter.next().unwrap();
-    let mut next_line = binding.as_ref();
-
-    while !next_line.is_empty() {
-        porder.add_rule(next_line);
-        binding = iter.next().unwrap();
-        next_line = binding.as_ref();
-    }
-    let pnums: Vec<_> = iter.map(|s| PageNumbers::parse_page_numbers(&s)).collect();
-    let mut middle_pages = 0;
-    for pnum in pnums {
-        if !pnum
-            .page_order()
-            .all(|(idx, ahead)| porder.valid(idx, &ahead))
-        {
-            let reordered = pnum.reorder_pages(&porder);
-            middle_pages += reordered.middle_page();
+    let mut lines = s.lines().enumerate();
+    for (idx, line) in lines.by_ref() {
+        if line.is_empty() {
+            break;
         }
+        porder.try_add_rule(line).map_err(|e| e.on_line(idx + 1))?;
     }
-    middle_pages
+    let pnums = lines
+        .map(|(idx, line)| {
+            PageNumbers::try_parse_page_numbers(line).map_err(|e| e.on_line(idx + 1))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((porder, pnums))
 }
 
-pub fn count_middle_pages<I: Iterator<Item = S>, S: AsRef<str>>(mut iter: I) -> usize {
-    let mut porder = PageOrdering::default();
-    let mut binding = iter.next().unwrap();
-    let mut next_line = binding.as_ref();
+pub fn parse_rules_pages(s: &str) -> (PageOrdering, Vec<PageNumbers>) {
+    try_parse_rules_pages(s).unwrap()
+}
 
-    while !next_line.is_empty() {
-        porder.add_rule(next_line);
-        binding = iter.next().unwrap();
-        next_line = binding.as_ref();
-    }
-    let pnums: Vec<_> = iter.map(|s| PageNumbers::parse_page_numbers(&s)).collect();
-    let mut middle_pages = 0;
-    for pnum in pnums {
-        if pnum
-            .page_order()
-            .all(|(idx, ahead)| porder.valid(idx, &ahead))
-        {
-            middle_pages += pnum.middle_page();
-        }
-    }
-    middle_pages
+/// [`parse_rules_pages`] on input already split into lines.
+fn parse_rules_pages_lines<I: Iterator<Item = S>, S: AsRef<str>>(
+    iter: I,
+) -> (PageOrdering, Vec<PageNumbers>) {
+    let lines: Vec<S> = iter.collect();
+    let lines: Vec<&str> = lines.iter().map(AsRef::as_ref).collect();
+    parse_rules_pages(&lines.join("\n"))
+}
+
+pub fn middle_pages(porder: &PageOrdering, pnums: &[PageNumbers]) -> usize {
+    pnums
+        .iter()
+        .filter(|pnum| {
+            pnum.page_order()
+                .all(|(idx, ahead)| porder.valid(idx, &ahead))
+        })
+        .map(PageNumbers::middle_page)
+        .sum()
+}
+
+pub fn wrong_middle_pages(porder: &PageOrdering, pnums: &[PageNumbers]) -> usize {
+    pnums
+        .iter()
+        .filter(|pnum| {
+            !pnum
+                .page_order()
+                .all(|(idx, ahead)| porder.valid(idx, &ahead))
+        })
+        .map(|pnum| pnum.reorder_pages(porder).middle_page())
+        .sum()
+}
+
+pub fn count_wrong_middle_pages<I: Iterator<Item = S>, S: AsRef<str>>(iter: I) -> usize {
+    let (porder, pnums) = parse_rules_pages_lines(iter);
+    wrong_middle_pages(&porder, &pnums)
 }
 
+pub fn count_middle_pages<I: Iterator<Item = S>, S: AsRef<str>>(iter: I) -> usize {
+    let (porder, pnums) = parse_rules_pages_lines(iter);
+    middle_pages(&porder, &pnums)
+}
+
+advent2024::example_tests!(Day05);
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_example() {
-        let example = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47
-";
+        let example = include_str!("../extra/example.txt");
         let (porder, pnums) = parse_rules_pages(example);
         assert_eq!(porder.befores.len(), 6);
 
@@ -197,43 +218,35 @@ mod tests {
     fn test_add_rule() {
         let rule = "72|26";
         let mut pordering = PageOrdering::default();
-        pordering.add_rule(rule);
+        pordering.try_add_rule(rule).unwrap();
         assert_eq!(
             pordering.befores.get(&72),
             Some(&[26].into_iter().collect::<HashSet<_>>())
         );
     }
+
+    #[test]
+    fn test_try_parse_rules_pages() {
+        let err = try_parse_rules_pages("47|53\n97-13\n\n75,47").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "97-13"));
+
+        let err = try_parse_rules_pages("47|53\n\n75,47\n75,,47").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (4, 4, ""));
+    }
+
+    #[test]
+    fn test_count_lines() {
+        let example = include_str!("../extra/example.txt");
+        assert_eq!(count_middle_pages(example.lines()), 143);
+        assert_eq!(
+            count_wrong_middle_pages(example.lines().map(String::from)),
+            123
+        );
+    }
+
     #[test]
     fn test_reorder() {
-        let example = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47
-";
+        let example = include_str!("../extra/example.txt");
         let (porder, _) = parse_rules_pages(example);
         let pnum = PageNumbers(vec![75, 97, 47, 61, 53]);
         assert!(!pnum
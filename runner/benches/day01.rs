@@ -0,0 +1,56 @@
+use std::hint::black_box;
+
+use advent2024::Solution;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod synthetic;
+
+/// Compares the line based parser with the byte level ones on `extra/input`.
+fn parsers(c: &mut Criterion) {
+    let input = day_01::Day01::default_input().read().unwrap();
+    let mut group = c.benchmark_group("day-01-parse");
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_function("parse_nums", |b| {
+        b.iter(|| day_01::parse_nums(black_box(&input).lines()))
+    });
+    group.bench_function("parse_columns", |b| {
+        b.iter(|| day_01::parse_columns::<usize>(black_box(input.as_bytes())).unwrap())
+    });
+    group.bench_function("read_columns", |b| {
+        b.iter(|| day_01::read_columns::<usize, _>(black_box(input.as_bytes())).unwrap())
+    });
+    group.finish();
+}
+
+/// `lines` random values in `lo..hi`.
+fn list(rng: &mut synthetic::Rng, lines: usize, lo: u64, hi: u64) -> Vec<usize> {
+    (0..lines).map(|_| rng.range(lo, hi) as usize).collect()
+}
+
+/// Compares sorting then taking the distance with `distance_unsorted`, on
+/// five digit ids as in the puzzle and on values too spread for counting.
+fn distances(c: &mut Criterion) {
+    let mut rng = synthetic::Rng::new(13);
+    let lines = 1_000_000;
+    let mut group = c.benchmark_group("day-01-distance");
+    group.throughput(Throughput::Elements(lines as u64));
+    for (name, hi) in [("5-digit", 100_000), ("wide", u64::MAX / 2)] {
+        let lists = (list(&mut rng, lines, 0, hi), list(&mut rng, lines, 0, hi));
+        group.bench_with_input(BenchmarkId::new("sort", name), &lists, |b, (l, r)| {
+            b.iter(|| {
+                let mut l = l.clone();
+                let mut r = r.clone();
+                l.sort();
+                r.sort();
+                day_01::distance(&l, &r)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("unsorted", name), &lists, |b, (l, r)| {
+            b.iter(|| day_01::distance_unsorted(black_box(l), black_box(r)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parsers, distances);
+criterion_main!(benches);
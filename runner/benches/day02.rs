@@ -0,0 +1,31 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_02::{FixedRows, Reports, SafetyPolicy};
+
+mod synthetic;
+
+/// Compares the slice-window safety check with the fixed-width rows.
+fn safety(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day-02-safety");
+    for lines in [1_000, 100_000] {
+        let reports = Reports::parse(&synthetic::day02(lines)).unwrap();
+        let narrow = FixedRows::<i8, 8>::from_reports(&reports).unwrap();
+        let wide = FixedRows::<i16, 8>::from_reports(&reports).unwrap();
+        let policy = SafetyPolicy::default();
+        group.throughput(Throughput::Elements(lines as u64));
+        group.bench_with_input(BenchmarkId::new("windows", lines), &reports, |b, r| {
+            b.iter(|| black_box(r).iter().filter(|l| day_02::is_safe(l)).count())
+        });
+        group.bench_with_input(BenchmarkId::new("i8x8", lines), &narrow, |b, r| {
+            b.iter(|| black_box(r).count_safe(&policy).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("i16x8", lines), &wide, |b, r| {
+            b.iter(|| black_box(r).count_safe(&policy).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, safety);
+criterion_main!(benches);
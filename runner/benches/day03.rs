@@ -0,0 +1,33 @@
+use std::hint::black_box;
+
+use advent2024::Solution;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use day_03::{Day03, InstructionSet};
+
+mod synthetic;
+
+/// Compares the nom parser with the hand-rolled scanner on `extra/input` and
+/// megabytes of generated corruption.
+fn parsers(c: &mut Criterion) {
+    let set = InstructionSet::part2();
+    let mut inputs = vec![("input".to_string(), Day03::default_input().read().unwrap())];
+    for megabytes in [1, 8] {
+        let input = synthetic::day03(megabytes << 20);
+        inputs.push((format!("{megabytes}-MiB"), input));
+    }
+
+    let mut group = c.benchmark_group("day-03-parse");
+    for (name, input) in &inputs {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_with_input(BenchmarkId::new("nom", name), input, |b, input| {
+            b.iter(|| set.parse(black_box(input)))
+        });
+        group.bench_with_input(BenchmarkId::new("scanner", name), input, |b, input| {
+            b.iter(|| set.scan(black_box(input)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parsers);
+criterion_main!(benches);
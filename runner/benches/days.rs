@@ -0,0 +1,63 @@
+use std::hint::black_box;
+
+use advent2024::Solution;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+mod synthetic;
+
+/// Benchmarks parsing and each part separately on one input.
+fn bench_input<S: Solution>(c: &mut Criterion, name: &str, input: &str) {
+    let mut group = c.benchmark_group(format!("day-{:02}", S::DAY));
+    group.throughput(Throughput::Bytes(input.len() as u64));
+    group.bench_with_input(BenchmarkId::new("parse", name), input, |b, input| {
+        b.iter(|| S::parse(black_box(input)).unwrap())
+    });
+    let parsed = S::parse(input).unwrap();
+    group.bench_with_input(BenchmarkId::new("part1", name), &parsed, |b, parsed| {
+        b.iter(|| black_box(S::part1(black_box(parsed))))
+    });
+    group.bench_with_input(BenchmarkId::new("part2", name), &parsed, |b, parsed| {
+        b.iter(|| black_box(S::part2(black_box(parsed))))
+    });
+    group.finish();
+}
+
+/// Benchmarks the day's real `extra/input` and each synthetic input.
+fn bench_day<S: Solution>(
+    c: &mut Criterion,
+    synthetic: impl IntoIterator<Item = (String, String)>,
+) {
+    let input = S::default_input().read().unwrap();
+    bench_input::<S>(c, "input", &input);
+    for (name, input) in synthetic {
+        bench_input::<S>(c, &name, &input);
+    }
+}
+
+fn day01(c: &mut Criterion) {
+    let inputs = [10_000, 100_000].map(|n| (format!("{n}-lines"), synthetic::day01(n)));
+    bench_day::<day_01::Day01>(c, inputs);
+}
+
+fn day02(c: &mut Criterion) {
+    let inputs = [10_000, 100_000].map(|n| (format!("{n}-lines"), synthetic::day02(n)));
+    bench_day::<day_02::Day02>(c, inputs);
+}
+
+fn day03(c: &mut Criterion) {
+    let inputs = [100_000, 1_000_000].map(|n| (format!("{n}-bytes"), synthetic::day03(n)));
+    bench_day::<day_03::Day03>(c, inputs);
+}
+
+fn day04(c: &mut Criterion) {
+    let inputs = [300, 1000].map(|n| (format!("{n}x{n}"), synthetic::day04(n)));
+    bench_day::<day_04::Day04>(c, inputs);
+}
+
+fn day05(c: &mut Criterion) {
+    let inputs = [1_000, 10_000].map(|n| (format!("{n}-updates"), synthetic::day05(49, n)));
+    bench_day::<day_05::Day05>(c, inputs);
+}
+
+criterion_group!(benches, day01, day02, day03, day04, day05);
+criterion_main!(benches);
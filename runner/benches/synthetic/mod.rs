@@ -0,0 +1,139 @@
+//! Deterministic generators for puzzle-shaped inputs of arbitrary size.
+#![allow(dead_code)]
+
+use std::fmt::Write;
+
+/// xorshift64, good enough to scatter benchmark inputs without pulling in `rand`.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed.max(1))
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform-ish value in `lo..hi`.
+    pub fn range(&mut self, lo: u64, hi: u64) -> u64 {
+        lo + self.next() % (hi - lo)
+    }
+
+    pub fn shuffle<T>(&mut self, xs: &mut [T]) {
+        for idx in (1..xs.len()).rev() {
+            xs.swap(idx, self.range(0, idx as u64 + 1) as usize);
+        }
+    }
+}
+
+/// `lines` rows of two five digit location ids, like day 1.
+pub fn day01(lines: usize) -> String {
+    let mut rng = Rng::new(1);
+    let mut out = String::new();
+    for _ in 0..lines {
+        let _ = writeln!(
+            out,
+            "{}   {}",
+            rng.range(10_000, 100_000),
+            rng.range(10_000, 100_000)
+        );
+    }
+    out
+}
+
+/// `lines` reports of 5 to 8 levels, most of them close to safe, like day 2.
+pub fn day02(lines: usize) -> String {
+    let mut rng = Rng::new(2);
+    let mut out = String::new();
+    for _ in 0..lines {
+        let len = rng.range(5, 9);
+        let increasing = rng.next() & 1 == 0;
+        let mut level = rng.range(10, 90) as i64;
+        for idx in 0..len {
+            if idx > 0 {
+                out.push(' ');
+            }
+            let _ = write!(out, "{level}");
+            let step = match rng.range(0, 20) {
+                0 => 0,
+                1 => 5,
+                _ => rng.range(1, 4) as i64,
+            };
+            level += if increasing { step } else { -step };
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// About `bytes` of corrupted memory with valid and broken instructions mixed
+/// into the junk, like day 3.
+pub fn day03(bytes: usize) -> String {
+    const JUNK: &[u8] = b"!@#$%^&*()[]{}<>,;: 'mudonwhtsel?+-_/";
+    const FRAGMENTS: &[&str] = &[
+        "mul(",
+        "mul[3,7]",
+        "do(",
+        "don't",
+        "mul(32,64]",
+        "mul ( 2 , 4 )",
+    ];
+    let mut rng = Rng::new(3);
+    let mut out = String::new();
+    while out.len() < bytes {
+        match rng.range(0, 20) {
+            0 => {
+                let _ = write!(out, "mul({},{})", rng.range(0, 1000), rng.range(0, 1000));
+            }
+            1 => out.push_str("do()"),
+            2 => out.push_str("don't()"),
+            3 => out.push_str(FRAGMENTS[rng.range(0, FRAGMENTS.len() as u64) as usize]),
+            _ => out.push(JUNK[rng.range(0, JUNK.len() as u64) as usize] as char),
+        }
+    }
+    out
+}
+
+/// A `size` by `size` grid of `X`, `M`, `A` and `S`, like day 4.
+pub fn day04(size: usize) -> String {
+    let mut rng = Rng::new(4);
+    let mut out = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        for _ in 0..size {
+            out.push(b"XMAS"[rng.range(0, 4) as usize] as char);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Ordering rules between every pair of `pages` pages, followed by `updates`
+/// shuffled updates of 5 to 23 pages, like day 5.
+pub fn day05(pages: usize, updates: usize) -> String {
+    let mut rng = Rng::new(5);
+    let mut order: Vec<usize> = (10..10 + pages).collect();
+    rng.shuffle(&mut order);
+
+    let mut out = String::new();
+    for (idx, before) in order.iter().enumerate() {
+        for after in &order[idx + 1..] {
+            let _ = writeln!(out, "{before}|{after}");
+        }
+    }
+    out.push('\n');
+    for _ in 0..updates {
+        let len = 2 * rng.range(2, 12) as usize + 1;
+        let mut update = order.clone();
+        rng.shuffle(&mut update);
+        let update: Vec<_> = update[..len.min(pages)]
+            .iter()
+            .map(usize::to_string)
+            .collect();
+        let _ = writeln!(out, "{}", update.join(","));
+    }
+    out
+}
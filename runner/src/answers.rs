@@ -0,0 +1,100 @@
+use std::{error::Error, fs, io, path::Path};
+
+use advent2024::Part;
+use serde::{Deserialize, Serialize};
+
+/// Known good answers for a day's default input, kept in `extra/answers.toml`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Match,
+    /// No answer was known yet, so this one was recorded.
+    Recorded,
+    Mismatch {
+        expected: String,
+    },
+}
+
+impl Answers {
+    /// Loads `path`, treating a missing file as having no answers yet.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                toml::from_str(&contents).map_err(|e| format!("{}: {e}", path.display()).into())
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("{}: {e}", path.display()).into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, toml::to_string(self)?).map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(())
+    }
+
+    fn get_mut(&mut self, part: Part) -> &mut Option<String> {
+        match part {
+            Part::One => &mut self.part1,
+            Part::Two => &mut self.part2,
+        }
+    }
+
+    /// Compares `answer` against the recorded one, recording it if missing.
+    pub fn check(&mut self, part: Part, answer: &str) -> Verdict {
+        match self.get_mut(part) {
+            Some(expected) if expected == answer => Verdict::Match,
+            Some(expected) => Verdict::Mismatch {
+                expected: expected.clone(),
+            },
+            missing @ None => {
+                *missing = Some(answer.to_owned());
+                Verdict::Recorded
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let mut answers = Answers::default();
+        assert_eq!(answers.check(Part::One, "11"), Verdict::Recorded);
+        assert_eq!(answers.check(Part::One, "11"), Verdict::Match);
+        assert_eq!(
+            answers.check(Part::One, "12"),
+            Verdict::Mismatch {
+                expected: "11".to_owned()
+            }
+        );
+        assert_eq!(answers.part2, None);
+    }
+
+    #[test]
+    fn test_load_save() {
+        let dir = std::env::temp_dir().join(format!("advent2024-answers-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("answers.toml");
+        let _ = fs::remove_file(&path);
+
+        let mut answers = Answers::load(&path).unwrap();
+        assert_eq!(answers, Answers::default());
+        answers.check(Part::Two, "31");
+        answers.save(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "part2 = \"31\"\n");
+        assert_eq!(Answers::load(&path).unwrap(), answers);
+
+        fs::write(&path, "part1 = [").unwrap();
+        assert!(Answers::load(&path).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    fmt::{self, Display},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+const YEAR: u16 = 2024;
+const USER_AGENT: &str = concat!(
+    "github.com/bsaintjo/advent2024 runner/",
+    env!("CARGO_PKG_VERSION")
+);
+
+#[derive(Debug)]
+pub enum FetchError {
+    /// The server answered with something other than 200, e.g. an expired
+    /// session or a puzzle that is not unlocked yet.
+    Status {
+        url: String,
+        code: u16,
+    },
+    Transport {
+        url: String,
+        message: String,
+    },
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Status { url, code } => write!(f, "{url}: server returned {code}"),
+            FetchError::Transport { url, message } => write!(f, "{url}: {message}"),
+            FetchError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+/// Whether a file had to be downloaded or was already on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    Downloaded,
+    Cached,
+    /// The puzzle page had no example to extract.
+    Missing,
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Client {
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session: session.to_owned(),
+        }
+    }
+
+    fn get(&self, path: &str) -> Result<String, FetchError> {
+        let url = format!("{}{path}", self.base_url);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+        match response {
+            Ok(response) => response.into_string().map_err(|e| FetchError::Transport {
+                url,
+                message: e.to_string(),
+            }),
+            Err(ureq::Error::Status(code, _)) => Err(FetchError::Status { url, code }),
+            Err(ureq::Error::Transport(e)) => Err(FetchError::Transport {
+                url,
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    pub fn input(&self, day: u8) -> Result<String, FetchError> {
+        self.get(&format!("/{YEAR}/day/{day}/input"))
+    }
+
+    /// The first code block of the puzzle description, which is the example.
+    pub fn example(&self, day: u8) -> Result<Option<String>, FetchError> {
+        let page = self.get(&format!("/{YEAR}/day/{day}"))?;
+        Ok(extract_example(&page))
+    }
+}
+
+fn extract_example(page: &str) -> Option<String> {
+    let start = page.find("<pre><code>")? + "<pre><code>".len();
+    let end = start + page[start..].find("</code></pre>")?;
+
+    let mut example = String::new();
+    let mut rest = &page[start..end];
+    while let Some(tag) = rest.find('<') {
+        example.push_str(&rest[..tag]);
+        rest = rest[tag..]
+            .find('>')
+            .map_or("", |close| &rest[tag + close + 1..]);
+    }
+    example.push_str(rest);
+    Some(
+        example
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), FetchError> {
+    let io_err = |source| FetchError::Io {
+        path: path.to_owned(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    fs::write(path, contents).map_err(io_err)
+}
+
+/// Downloads the input and example for `day` into `extra`, leaving files that
+/// are already cached untouched. Returns what happened to each, input first.
+///
+/// An empty `input` is the placeholder `new-day` leaves, not a cached input.
+pub fn fetch(client: &Client, extra: &Path, day: u8) -> Result<[Fetched; 2], FetchError> {
+    let input_path = extra.join("input");
+    let cached = fs::metadata(&input_path).is_ok_and(|meta| meta.len() > 0);
+    let input = if cached {
+        Fetched::Cached
+    } else {
+        write(&input_path, &client.input(day)?)?;
+        Fetched::Downloaded
+    };
+
+    let example_path = extra.join("example.txt");
+    let example = if example_path.exists() {
+        Fetched::Cached
+    } else {
+        match client.example(day)? {
+            Some(example) => {
+                write(&example_path, &example)?;
+                Fetched::Downloaded
+            }
+            None => Fetched::Missing,
+        }
+    };
+    Ok([input, example])
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use super::*;
+
+    type Requests = Arc<Mutex<Vec<String>>>;
+
+    /// Serves `routes` as `(path, status, body)` over HTTP on localhost and
+    /// records the head of every request it receives.
+    fn mock_server(routes: Vec<(&'static str, u16, &'static str)>) -> (String, Requests) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Requests::default();
+        let recorded = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut head = String::new();
+                let mut reader = BufReader::new(&stream);
+                loop {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line).unwrap() == 0 || line == "\r\n" {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                let path = head.split(' ').nth(1).unwrap_or_default().to_owned();
+                recorded.lock().unwrap().push(head);
+
+                let (status, body) = routes
+                    .iter()
+                    .find(|route| route.0 == path)
+                    .map_or((404, ""), |route| (route.1, route.2));
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (base_url, requests)
+    }
+
+    fn temp_extra(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("advent2024-fetch-{}-{name}", std::process::id()))
+            .join("extra");
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    const PAGE: &str = "<article><p>For example:</p>\
+        <pre><code>3   4\n<em>4</em>   3\n&lt;&amp;&gt;\n</code></pre>\
+        <pre><code>ignored</code></pre></article>";
+
+    #[test]
+    fn test_extract_example() {
+        assert_eq!(
+            extract_example(PAGE).as_deref(),
+            Some("3   4\n4   3\n<&>\n")
+        );
+        assert_eq!(extract_example("<p>no code</p>"), None);
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let (base_url, requests) = mock_server(vec![
+            ("/2024/day/1/input", 200, "1 2\n"),
+            ("/2024/day/1", 200, PAGE),
+        ]);
+        let extra = temp_extra("cache");
+        let client = Client::new(&format!("{base_url}/"), "abc123");
+
+        let fetched = fetch(&client, &extra, 1).unwrap();
+        assert_eq!(fetched, [Fetched::Downloaded, Fetched::Downloaded]);
+        assert_eq!(fs::read_to_string(extra.join("input")).unwrap(), "1 2\n");
+        assert_eq!(
+            fs::read_to_string(extra.join("example.txt")).unwrap(),
+            "3   4\n4   3\n<&>\n"
+        );
+        {
+            let requests = requests.lock().unwrap();
+            assert_eq!(requests.len(), 2);
+            assert!(requests[0].starts_with("GET /2024/day/1/input HTTP/1.1\r\n"));
+            for request in requests.iter() {
+                let request = request.to_lowercase();
+                assert!(request.contains("cookie: session=abc123\r\n"));
+                assert!(request.contains("user-agent: github.com/bsaintjo/advent2024"));
+            }
+        }
+
+        let fetched = fetch(&client, &extra, 1).unwrap();
+        assert_eq!(fetched, [Fetched::Cached, Fetched::Cached]);
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let (base_url, _) = mock_server(vec![("/2024/day/2/input", 400, "bad session")]);
+        let extra = temp_extra("errors");
+        let client = Client::new(&base_url, "expired");
+
+        let err = fetch(&client, &extra, 2).unwrap_err();
+        assert!(matches!(err, FetchError::Status { code: 400, .. }));
+        assert!(!extra.join("input").exists());
+
+        let err = fetch(&client, &extra, 3).unwrap_err();
+        assert!(matches!(err, FetchError::Status { code: 404, .. }));
+
+        let client = Client::new("http://127.0.0.1:1", "abc123");
+        let err = fetch(&client, &extra, 2).unwrap_err();
+        assert!(matches!(err, FetchError::Transport { .. }));
+    }
+
+    #[test]
+    fn test_missing_example() {
+        let (base_url, _) = mock_server(vec![
+            ("/2024/day/4/input", 200, "XMAS\n"),
+            ("/2024/day/4", 200, "<p>no code</p>"),
+        ]);
+        let extra = temp_extra("missing");
+        let client = Client::new(&base_url, "abc123");
+        // The empty placeholder left by `new-day` is replaced
+        write(&extra.join("input"), "").unwrap();
+        let fetched = fetch(&client, &extra, 4).unwrap();
+        assert_eq!(fetched, [Fetched::Downloaded, Fetched::Missing]);
+        assert_eq!(fs::read_to_string(extra.join("input")).unwrap(), "XMAS\n");
+        assert!(!extra.join("example.txt").exists());
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    error::Error,
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::Duration,
+};
+
+use clap::{Args, Parser, Subcommand};
+
+mod answers;
+mod fetch;
+mod registry;
+mod scaffold;
+
+use advent2024::{InputSource, Part};
+use answers::{Answers, Verdict};
+use registry::{Answer, Solved, Solver};
+
+#[derive(Parser)]
+#[command(name = "advent2024", about = "Advent of Code 2024 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one or every day and print a table of answers with timings
+    Run(RunArgs),
+    /// Download a day's input and example into its `extra/` directory
+    Fetch(FetchArgs),
+    /// Check every day's answers against its `extra/answers.toml`, recording
+    /// answers that are not known yet
+    Verify(VerifyArgs),
+    /// Generate the crate for a new day and register it everywhere
+    NewDay {
+        /// Day to create
+        day: u8,
+    },
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to run
+    #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+    day: Option<u8>,
+    /// Part to run, both parts are run when omitted
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Puzzle input, `-` for stdin, defaults to the day's `extra/input`
+    #[arg(long, conflicts_with = "all")]
+    input: Option<OsString>,
+    /// Run every registered day
+    #[arg(long)]
+    all: bool,
+}
+
+#[derive(Args)]
+struct FetchArgs {
+    /// Day to download
+    #[arg(long)]
+    day: u8,
+    /// Server to download from
+    #[arg(long, env = "AOC_BASE_URL", default_value = "https://adventofcode.com")]
+    base_url: String,
+    /// Value of the `session` cookie of a logged in browser
+    #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+    session: String,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// Only verify this day
+    #[arg(long)]
+    day: Option<u8>,
+}
+
+fn workspace_root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("runner lives inside the workspace")
+}
+
+fn day_dir(day: u8) -> PathBuf {
+    workspace_root().join("days").join(format!("day-{day:02}"))
+}
+
+fn solve(solver: &Solver, parts: &[Part], source: &InputSource) -> Result<Solved, Box<dyn Error>> {
+    let input = source
+        .read()
+        .map_err(|e| format!("day {}: {e}", solver.day))?;
+    let solved = solver
+        .solve(&input, parts)
+        .map_err(|e| format!("day {}: {e}", solver.day))?;
+    Ok(solved)
+}
+
+fn print_table(days: &[(u8, Solved)]) {
+    let width = days
+        .iter()
+        .flat_map(|(_, solved)| &solved.answers)
+        .map(|a| a.answer.len())
+        .chain(Some("Answer".len()))
+        .max()
+        .unwrap_or_default();
+    println!(
+        "Day  Part  {:<width$}  {:>10}  {:>10}",
+        "Answer", "Parse", "Solve"
+    );
+    let mut total = Duration::ZERO;
+    for (day, solved) in days {
+        total += solved.parse;
+        for (idx, answer) in solved.answers.iter().enumerate() {
+            // Parsing is shared by both parts, so only show it once per day
+            let parse = if idx == 0 {
+                format!("{:.3?}", solved.parse)
+            } else {
+                String::new()
+            };
+            println!(
+                "{day:>3}  {:>4}  {:<width$}  {parse:>10}  {:>10}",
+                answer.part,
+                answer.answer,
+                format!("{:.3?}", answer.elapsed),
+            );
+            total += answer.elapsed;
+        }
+    }
+    println!(
+        "{:>9}  {:<width$}  {:>22}",
+        "",
+        "Total",
+        format!("{total:.3?}")
+    );
+}
+
+fn run(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let parts = match args.part.map(Part::try_from) {
+        Some(Ok(part)) => vec![part],
+        Some(Err(part)) => return Err(format!("part {part} does not exist").into()),
+        None => Part::ALL.to_vec(),
+    };
+    let solvers = match args.day {
+        Some(day) => vec![registry::find(day).ok_or(format!("day {day} has no solution"))?],
+        None => registry::SOLVERS.iter().collect(),
+    };
+
+    let mut days = Vec::new();
+    for solver in solvers {
+        let source = InputSource::from_arg(args.input.clone(), solver.default_input());
+        days.push((solver.day, solve(solver, &parts, &source)?));
+    }
+    print_table(&days);
+    Ok(())
+}
+
+fn fetch(args: FetchArgs) -> Result<(), Box<dyn Error>> {
+    let client = fetch::Client::new(&args.base_url, &args.session);
+    let extra = day_dir(args.day).join("extra");
+    let [input, example] = fetch::fetch(&client, &extra, args.day)?;
+    for (name, fetched) in [("input", input), ("example.txt", example)] {
+        let status = match fetched {
+            fetch::Fetched::Downloaded => "downloaded",
+            fetch::Fetched::Cached => "already cached",
+            fetch::Fetched::Missing => "not found on the puzzle page",
+        };
+        println!("{}: {status}", extra.join(name).display());
+    }
+    Ok(())
+}
+
+fn verify(args: VerifyArgs) -> Result<(), Box<dyn Error>> {
+    let solvers = match args.day {
+        Some(day) => vec![registry::find(day).ok_or(format!("day {day} has no solution"))?],
+        None => registry::SOLVERS.iter().collect(),
+    };
+
+    let mut failures = 0;
+    println!("Day  Part  Status");
+    for solver in solvers {
+        let path = day_dir(solver.day).join("extra").join("answers.toml");
+        let mut answers = Answers::load(&path)?;
+        let solved = match solve(solver, &Part::ALL, &solver.default_input()) {
+            Ok(solved) => solved,
+            Err(e) => {
+                println!("{:>3}  {:>4}  FAILED {e}", solver.day, "-");
+                failures += 1;
+                continue;
+            }
+        };
+
+        let mut recorded = false;
+        for Answer { part, answer, .. } in &solved.answers {
+            let status = match answers.check(*part, answer) {
+                Verdict::Match => format!("ok {answer}"),
+                Verdict::Recorded => {
+                    recorded = true;
+                    format!("recorded {answer}")
+                }
+                Verdict::Mismatch { expected } => {
+                    failures += 1;
+                    format!("MISMATCH expected {expected}, got {answer}")
+                }
+            };
+            println!("{:>3}  {part:>4}  {status}", solver.day);
+        }
+        if recorded {
+            answers.save(&path)?;
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} answer(s) failed verification").into());
+    }
+    Ok(())
+}
+
+fn new_day(day: u8) -> Result<(), Box<dyn Error>> {
+    for path in scaffold::new_day(workspace_root(), day)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Run(args) => run(args),
+        Command::Fetch(args) => fetch(args),
+        Command::Verify(args) => verify(args),
+        Command::NewDay { day } => new_day(day),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
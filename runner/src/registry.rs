@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+use advent2024::{InputSource, ParseError, Part, Solution};
+
+/// Answer to one part along with how long it took to compute.
+pub struct Answer {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Outcome of parsing a day's input and solving the requested parts.
+pub struct Solved {
+    pub parse: Duration,
+    pub answers: Vec<Answer>,
+}
+
+/// Type-erased entry in the registry, so days can be looked up at runtime.
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub day: u8,
+    default_input: fn() -> InputSource,
+    solve: fn(&str, &[Part]) -> Result<Solved, ParseError>,
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Solved, ParseError> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse = start.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = S::solve(&input, part);
+            Answer {
+                part,
+                answer,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+    Ok(Solved { parse, answers })
+}
+
+impl Solver {
+    pub const fn of<S: Solution>() -> Self {
+        Solver {
+            day: S::DAY,
+            default_input: S::default_input,
+            solve: solve::<S>,
+        }
+    }
+
+    pub fn default_input(&self) -> InputSource {
+        (self.default_input)()
+    }
+
+    pub fn solve(&self, input: &str, parts: &[Part]) -> Result<Solved, ParseError> {
+        (self.solve)(input, parts)
+    }
+}
+
+/// Every solved day, in order. Adding a day means adding its type here.
+pub const SOLVERS: &[Solver] = &[
+    Solver::of::<day_01::Day01>(),
+    Solver::of::<day_02::Day02>(),
+    Solver::of::<day_03::Day03>(),
+    Solver::of::<day_04::Day04>(),
+    Solver::of::<day_05::Day05>(),
+];
+
+pub fn find(day: u8) -> Option<&'static Solver> {
+    SOLVERS.iter().find(|s| s.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry_sorted_and_unique() {
+        assert!(SOLVERS.windows(2).all(|w| w[0].day < w[1].day));
+        assert_eq!(find(3).map(|s| s.day), Some(3));
+        assert!(find(25).is_none());
+    }
+
+    #[test]
+    fn test_solve_parts() {
+        let solved = find(3)
+            .unwrap()
+            .solve("mul(2,4)don't()mul(5,5)", &Part::ALL)
+            .unwrap();
+        let answers: Vec<_> = solved.answers.iter().map(|a| a.answer.as_str()).collect();
+        assert_eq!(answers, ["33", "8"]);
+    }
+}
@@ -0,0 +1,292 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use toml_edit::{value, Array, DocumentMut, InlineTable, Value};
+
+const CARGO_TOML: &str = include_str!("../templates/Cargo.toml.tmpl");
+const BUILD_RS: &str = include_str!("../templates/build.rs.tmpl");
+const LIB_RS: &str = include_str!("../templates/lib.rs.tmpl");
+const PART_RS: &str = include_str!("../templates/part.rs.tmpl");
+
+const SOLVERS_START: &str = "pub const SOLVERS: &[Solver] = &[\n";
+
+fn render(template: &str, day: u8, part: &str) -> String {
+    template
+        .replace("{{DAY}}", &format!("{day:02}"))
+        .replace("{{DAY_NUM}}", &day.to_string())
+        .replace("{{PART}}", part)
+}
+
+fn read(path: &Path) -> Result<String, Box<dyn Error>> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()).into())
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("{}: {e}", parent.display()))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("{}: {e}", path.display()).into())
+}
+
+/// Inserts `item` into a sorted array of strings, keeping it sorted and
+/// keeping multi-line arrays at one item per line.
+fn insert_sorted(array: &mut Array, item: &str) {
+    let multiline = array.iter().any(|v| {
+        v.decor()
+            .prefix()
+            .and_then(|p| p.as_str())
+            .is_some_and(|p| p.contains('\n'))
+    });
+    let idx = array
+        .iter()
+        .position(|v| v.as_str().is_some_and(|v| v > item))
+        .unwrap_or(array.len());
+    array.insert(idx, item);
+    if multiline {
+        for v in array.iter_mut() {
+            v.decor_mut().set_prefix("\n    ");
+            v.decor_mut().set_suffix("");
+        }
+        array.set_trailing("\n");
+        array.set_trailing_comma(true);
+    } else {
+        array.fmt();
+    }
+}
+
+fn add_workspace_member(manifest: &str, day: u8) -> Result<String, Box<dyn Error>> {
+    let mut doc: DocumentMut = manifest.parse()?;
+    let members = doc["workspace"]["members"]
+        .as_array_mut()
+        .ok_or("workspace has no members array")?;
+    insert_sorted(members, &format!("days/day-{day:02}"));
+    Ok(doc.to_string())
+}
+
+fn add_runner_dependency(manifest: &str, day: u8) -> Result<String, Box<dyn Error>> {
+    let mut doc: DocumentMut = manifest.parse()?;
+    let mut dep = InlineTable::new();
+    dep.insert("path", Value::from(format!("../days/day-{day:02}")));
+    doc["dependencies"][&format!("day-{day:02}")] = value(dep);
+    let embed = doc["features"]["embed-input"]
+        .as_array_mut()
+        .ok_or("runner has no embed-input feature")?;
+    insert_sorted(embed, &format!("day-{day:02}/embed-input"));
+    Ok(doc.to_string())
+}
+
+fn add_registry_entry(registry: &str, day: u8) -> Result<String, Box<dyn Error>> {
+    let start = registry
+        .find(SOLVERS_START)
+        .ok_or("registry has no SOLVERS list")?
+        + SOLVERS_START.len();
+    let end = start
+        + registry[start..]
+            .find("];")
+            .ok_or("SOLVERS list is not closed")?;
+
+    // Keep the list sorted by day, entries are all of the same form
+    let crate_name = format!("day_{day:02}");
+    let mut at = end;
+    let mut offset = start;
+    for line in registry[start..end].split_inclusive('\n') {
+        if line.trim_start() > format!("Solver::of::<{crate_name}").as_str() {
+            at = offset;
+            break;
+        }
+        offset += line.len();
+    }
+    let entry = format!("    Solver::of::<{crate_name}::Day{day:02}>(),\n");
+    Ok(format!("{}{entry}{}", &registry[..at], &registry[at..]))
+}
+
+/// Creates the `days/day-NN` crate for `day` under the workspace `root` and
+/// registers it with the workspace and the runner. Returns every file created
+/// or modified.
+///
+/// `extra/input` starts out empty, so that the runner still builds with
+/// `embed-input` before the input is fetched.
+pub fn new_day(root: &Path, day: u8) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("day {day} is not between 1 and 25").into());
+    }
+    let dir = root.join("days").join(format!("day-{day:02}"));
+    if dir.exists() {
+        return Err(format!("{} already exists", dir.display()).into());
+    }
+
+    // Work out every edit up front so a bad manifest leaves nothing half done
+    let workspace = root.join("Cargo.toml");
+    let runner = root.join("runner").join("Cargo.toml");
+    let registry = root.join("runner").join("src").join("registry.rs");
+    let edits = [
+        (
+            workspace.clone(),
+            add_workspace_member(&read(&workspace)?, day)?,
+        ),
+        (runner.clone(), add_runner_dependency(&read(&runner)?, day)?),
+        (
+            registry.clone(),
+            add_registry_entry(&read(&registry)?, day)?,
+        ),
+    ];
+
+    let files = [
+        (dir.join("Cargo.toml"), render(CARGO_TOML, day, "")),
+        (dir.join("build.rs"), render(BUILD_RS, day, "")),
+        (dir.join("src").join("lib.rs"), render(LIB_RS, day, "")),
+        (
+            dir.join("src").join("bin").join("part01.rs"),
+            render(PART_RS, day, "One"),
+        ),
+        (
+            dir.join("src").join("bin").join("part02.rs"),
+            render(PART_RS, day, "Two"),
+        ),
+        (dir.join("extra").join("input"), String::new()),
+    ];
+
+    let mut touched = Vec::new();
+    for (path, contents) in files.into_iter().chain(edits) {
+        write(&path, &contents)?;
+        touched.push(path);
+    }
+    Ok(touched)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const WORKSPACE: &str = r#"[workspace]
+members = ["days/day-01", "days/day-03", "runner"]
+
+[package]
+name = "advent2024"
+"#;
+
+    const RUNNER: &str = r#"[package]
+name = "runner"
+
+[dependencies]
+advent2024 = { path = ".." }
+day-01 = { path = "../days/day-01" }
+day-03 = { path = "../days/day-03" }
+
+[features]
+embed-input = [
+    "day-01/embed-input",
+    "day-03/embed-input",
+]
+"#;
+
+    const REGISTRY: &str = "pub const SOLVERS: &[Solver] = &[
+    Solver::of::<day_01::Day01>(),
+    Solver::of::<day_03::Day03>(),
+];
+";
+
+    #[test]
+    fn test_add_workspace_member() {
+        let manifest = add_workspace_member(WORKSPACE, 2).unwrap();
+        assert!(manifest
+            .contains(r#"members = ["days/day-01", "days/day-02", "days/day-03", "runner"]"#));
+    }
+
+    #[test]
+    fn test_add_runner_dependency() {
+        let manifest = add_runner_dependency(RUNNER, 4).unwrap();
+        assert!(manifest.contains(
+            "day-03 = { path = \"../days/day-03\" }\nday-04 = { path = \"../days/day-04\" }\n"
+        ));
+        assert!(manifest.contains(
+            "embed-input = [\n    \"day-01/embed-input\",\n    \"day-03/embed-input\",\n    \"day-04/embed-input\",\n]\n"
+        ));
+    }
+
+    #[test]
+    fn test_add_registry_entry() {
+        assert_eq!(
+            add_registry_entry(REGISTRY, 2).unwrap(),
+            "pub const SOLVERS: &[Solver] = &[
+    Solver::of::<day_01::Day01>(),
+    Solver::of::<day_02::Day02>(),
+    Solver::of::<day_03::Day03>(),
+];
+"
+        );
+        assert!(add_registry_entry(REGISTRY, 12)
+            .unwrap()
+            .contains("Day03>(),\n    Solver::of::<day_12::Day12>(),\n];"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = std::env::temp_dir().join(format!("advent2024-scaffold-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        write(&root.join("Cargo.toml"), WORKSPACE).unwrap();
+        write(&root.join("runner/Cargo.toml"), RUNNER).unwrap();
+        write(&root.join("runner/src/registry.rs"), REGISTRY).unwrap();
+
+        let touched = new_day(&root, 6).unwrap();
+        assert_eq!(touched.len(), 9);
+        assert_eq!(
+            fs::read_to_string(root.join("days/day-06/extra/input")).unwrap(),
+            ""
+        );
+        let lib = fs::read_to_string(root.join("days/day-06/src/lib.rs")).unwrap();
+        assert!(lib.contains("impl Solution for Day06 {\n    const DAY: u8 = 6;"));
+        let bin = fs::read_to_string(root.join("days/day-06/src/bin/part02.rs")).unwrap();
+        assert!(bin.contains("advent2024::run_part::<day_06::Day06>(Part::Two)"));
+
+        let registry = fs::read_to_string(root.join("runner/src/registry.rs")).unwrap();
+        let err = new_day(&root, 6).unwrap_err();
+        assert!(err.to_string().ends_with("already exists"));
+        assert_eq!(
+            fs::read_to_string(root.join("runner/src/registry.rs")).unwrap(),
+            registry
+        );
+        assert!(new_day(&root, 26).is_err());
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    /// Scaffolds a day next to a copy of the `advent2024` crate and builds it
+    /// with its input embedded. Slow and needs every dependency in the
+    /// offline cache, so only run with `cargo test -- --ignored`.
+    #[test]
+    #[ignore = "runs cargo build on a copied workspace"]
+    fn test_new_day_builds_embedded() {
+        let root = std::env::temp_dir().join(format!("advent2024-embed-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let real = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        let mut manifest: DocumentMut = read(&real.join("Cargo.toml")).unwrap().parse().unwrap();
+        manifest["workspace"]["members"] = value(Array::new());
+        write(&root.join("Cargo.toml"), &manifest.to_string()).unwrap();
+        let src = real.join("src");
+        for entry in fs::read_dir(&src).unwrap() {
+            let path = entry.unwrap().path();
+            write(
+                &root.join("src").join(path.file_name().unwrap()),
+                &read(&path).unwrap(),
+            )
+            .unwrap();
+        }
+        write(&root.join("runner/Cargo.toml"), RUNNER).unwrap();
+        write(&root.join("runner/src/registry.rs"), REGISTRY).unwrap();
+        new_day(&root, 6).unwrap();
+
+        let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".into());
+        let status = std::process::Command::new(cargo)
+            .args(["build", "--offline", "--quiet", "-p", "day-06"])
+            .args(["--features", "embed-input"])
+            .current_dir(&root)
+            .env("CARGO_TARGET_DIR", root.join("target"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
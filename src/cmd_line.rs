@@ -0,0 +1,92 @@
+use std::{env, ffi::OsString};
+
+use crate::{Error, InputSource};
+
+/// Arguments of the `partNN` binaries: at most one input, `-` for stdin, and
+/// any number of `--flag` or `--flag=value` options.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CmdLine {
+    input: Option<OsString>,
+    flags: Vec<String>,
+}
+
+impl CmdLine {
+    pub fn from_env() -> Result<Self, Error> {
+        Self::parse(env::args_os().skip(1))
+    }
+
+    pub fn parse<I: IntoIterator<Item = OsString>>(args: I) -> Result<Self, Error> {
+        let mut cmd = CmdLine::default();
+        for arg in args {
+            match arg.to_str() {
+                Some(flag) if flag.starts_with("--") => cmd.flags.push(flag.to_owned()),
+                _ if cmd.input.is_some() => {
+                    return Err(Error::Usage(format!(
+                        "unexpected argument {}",
+                        arg.to_string_lossy()
+                    )))
+                }
+                _ => cmd.input = Some(arg),
+            }
+        }
+        Ok(cmd)
+    }
+
+    fn names(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.flags.iter().map(|flag| match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (flag.as_str(), None),
+        })
+    }
+
+    /// Whether `--name` was given, with or without a value.
+    pub fn flag(&self, name: &str) -> bool {
+        self.names().any(|(n, _)| n == name)
+    }
+
+    /// The value of the last `--name=value`.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.names()
+            .filter(|(n, _)| *n == name)
+            .filter_map(|(_, value)| value)
+            .last()
+    }
+
+    /// Rejects every flag that is not one of `known`.
+    pub fn expect_flags(&self, known: &[&str]) -> Result<(), Error> {
+        match self.names().find(|(name, _)| !known.contains(name)) {
+            Some((name, _)) => Err(Error::Usage(format!("unknown flag {name}"))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn source(&self, default: InputSource) -> InputSource {
+        InputSource::from_arg(self.input.clone(), default)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CmdLine, Error> {
+        CmdLine::parse(args.iter().map(OsString::from))
+    }
+
+    #[test]
+    fn test_parse() {
+        let cmd = parse(&["--json", "extra/input", "--explain=csv"]).unwrap();
+        assert_eq!(cmd.input, Some("extra/input".into()));
+        assert!(cmd.flag("--json"));
+        assert!(cmd.flag("--explain"));
+        assert!(!cmd.flag("--report"));
+        assert_eq!(cmd.value("--explain"), Some("csv"));
+        assert_eq!(cmd.value("--json"), None);
+        assert!(cmd.expect_flags(&["--json", "--explain"]).is_ok());
+        assert!(cmd.expect_flags(&["--json"]).is_err());
+
+        let cmd = parse(&["-"]).unwrap();
+        assert_eq!(cmd.source(InputSource::Embedded("")), InputSource::Stdin);
+        assert!(parse(&["a", "b"]).is_err());
+    }
+}
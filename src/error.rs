@@ -0,0 +1,128 @@
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    /// No input file was given on the command line.
+    MissingArg,
+    /// Bad command line arguments.
+    Usage(String),
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse(ParseError),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingArg => write!(f, "missing input file argument"),
+            Error::Usage(message) => write!(f, "{message}"),
+            Error::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Error::Parse(e) => write!(f, "parse error at {e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Parse(e) => Some(e),
+            Error::MissingArg | Error::Usage(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(e: ParseError) -> Self {
+        Error::Parse(e)
+    }
+}
+
+/// Malformed input, located by 1-based line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// The offending text, empty when the line ended too early.
+    pub text: String,
+    pub expected: &'static str,
+}
+
+impl ParseError {
+    /// Error for `token`, which must be a subslice of `source`. When `source`
+    /// is a single line the line number is 1, callers parsing line by line fix
+    /// it with [`ParseError::on_line`].
+    pub fn new(source: &str, token: &str, expected: &'static str) -> Self {
+        let offset = (token.as_ptr() as usize)
+            .saturating_sub(source.as_ptr() as usize)
+            .min(source.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        ParseError {
+            line: before.matches('\n').count() + 1,
+            column: offset - line_start + 1,
+            text: token.to_owned(),
+            expected,
+        }
+    }
+
+    /// Error for a line that ended before `expected` was found.
+    pub fn end_of_line(line: &str, expected: &'static str) -> Self {
+        ParseError::new(line, &line[line.len()..], expected)
+    }
+
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, ", found end of line")
+        } else {
+            write!(f, ", found `{}`", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_column() {
+        let line = "12 x4 7";
+        let err = ParseError::new(line, &line[3..5], "a number").on_line(4);
+        assert_eq!((err.line, err.column), (4, 4));
+        assert_eq!(
+            Error::from(err).to_string(),
+            "parse error at line 4, column 4: expected a number, found `x4`"
+        );
+
+        let source = "1 2\n3 4\n5 ?";
+        let err = ParseError::new(source, &source[10..], "a number");
+        assert_eq!((err.line, err.column), (3, 3));
+
+        let err = ParseError::end_of_line(line, "a number");
+        assert_eq!(err.column, 8);
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 8: expected a number, found end of line"
+        );
+    }
+}
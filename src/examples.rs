@@ -0,0 +1,147 @@
+//! Tests generated from the example inputs kept in each day's `extra/`.
+//!
+//! Every `extra/example*.txt` is an example input. Its expected answers live
+//! next to it in files named after it, `example.part1` and `example.part2`
+//! for `example.txt`, either of which may be left out. A day opts in by
+//! calling [`generate`] from its `build.rs` and [`example_tests!`] from its
+//! `lib.rs`, which adds one test per example per answer.
+//!
+//! [`example_tests!`]: crate::example_tests
+
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{Part, Solution};
+
+/// Parses `input` and checks the answer to `part` against `expected`.
+pub fn check<S: Solution>(input: &str, expected: &str, part: Part) {
+    let parsed = S::parse(input).unwrap_or_else(|e| panic!("example does not parse: {e}"));
+    assert_eq!(S::solve(&parsed, part), expected.trim());
+}
+
+/// Every `(test name, example, part, expected answer file)` found in `extra`.
+fn discover(extra: &Path) -> Vec<(String, PathBuf, Part, PathBuf)> {
+    let mut examples: Vec<_> = fs::read_dir(extra)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            name.starts_with("example") && name.ends_with(".txt")
+        })
+        .collect();
+    examples.sort();
+
+    let mut tests = Vec::new();
+    for example in examples {
+        let stem = example.file_stem().and_then(|s| s.to_str()).unwrap();
+        let name: String = stem
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        for (part, ext) in [(Part::One, "part1"), (Part::Two, "part2")] {
+            let expected = example.with_extension(ext);
+            if expected.exists() {
+                tests.push((format!("{name}_{ext}"), example.clone(), part, expected));
+            }
+        }
+    }
+    tests
+}
+
+/// Build script entry point, writes the example tests of the crate being
+/// built to `$OUT_DIR/examples.rs`.
+pub fn generate() {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").expect("run from a build script");
+    let out_dir = env::var_os("OUT_DIR").expect("run from a build script");
+    let extra = Path::new(&manifest_dir).join("extra");
+    println!("cargo::rerun-if-changed={}", extra.display());
+
+    let mut out = String::new();
+    for (name, example, part, expected) in discover(&extra) {
+        let example = example.display().to_string();
+        let expected = expected.display().to_string();
+        let _ = writeln!(out, "#[test]\nfn {name}() {{");
+        let _ = writeln!(out, "    let input = include_str!({example:?});");
+        let _ = writeln!(out, "    let expected = include_str!({expected:?});");
+        let _ = writeln!(
+            out,
+            "    check(input, expected, ::advent2024::Part::{part:?});\n}}"
+        );
+    }
+    fs::write(Path::new(&out_dir).join("examples.rs"), out).expect("failed to write example tests");
+}
+
+/// Adds the tests generated by [`generate`] for `$solution`.
+#[macro_export]
+macro_rules! example_tests {
+    ($solution:ty) => {
+        #[cfg(test)]
+        mod examples {
+            use super::*;
+
+            fn check(input: &str, expected: &str, part: $crate::Part) {
+                $crate::examples::check::<$solution>(input, expected, part)
+            }
+
+            include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let extra = env::temp_dir().join(format!("advent2024-examples-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&extra);
+        fs::create_dir_all(&extra).unwrap();
+        for file in [
+            "example.txt",
+            "example.part1",
+            "example.part2",
+            "example-2.txt",
+            "example-2.part2",
+            "example-3.txt",
+            "input",
+        ] {
+            fs::write(extra.join(file), "").unwrap();
+        }
+
+        let tests: Vec<_> = discover(&extra)
+            .into_iter()
+            .map(|(name, _, part, expected)| (name, part, expected.file_name().unwrap().to_owned()))
+            .collect();
+        assert_eq!(
+            tests,
+            [
+                (
+                    "example_2_part2".to_owned(),
+                    Part::Two,
+                    "example-2.part2".into()
+                ),
+                (
+                    "example_part1".to_owned(),
+                    Part::One,
+                    "example.part1".into()
+                ),
+                (
+                    "example_part2".to_owned(),
+                    Part::Two,
+                    "example.part2".into()
+                ),
+            ]
+        );
+        fs::remove_dir_all(&extra).unwrap();
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    ffi::OsString,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::PathBuf,
+};
+
+use crate::Error;
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Stdin,
+    Path(PathBuf),
+    /// Input compiled into the binary, see [`default_input!`](crate::default_input).
+    Embedded(&'static str),
+}
+
+impl InputSource {
+    /// Interprets a command line argument: `-` is stdin, anything else a path,
+    /// and a missing argument falls back to `default`.
+    pub fn from_arg(arg: Option<OsString>, default: InputSource) -> Self {
+        match arg {
+            Some(arg) if arg == "-" => InputSource::Stdin,
+            Some(arg) => InputSource::Path(arg.into()),
+            None => default,
+        }
+    }
+
+    pub fn read(&self) -> Result<String, Error> {
+        match self {
+            InputSource::Stdin => {
+                let mut buf = String::new();
+                io::stdin()
+                    .read_to_string(&mut buf)
+                    .map_err(|source| Error::Io {
+                        path: "<stdin>".into(),
+                        source,
+                    })?;
+                Ok(buf)
+            }
+            InputSource::Path(path) => fs::read_to_string(path).map_err(|source| Error::Io {
+                path: path.clone(),
+                source,
+            }),
+            InputSource::Embedded(input) => Ok((*input).to_owned()),
+        }
+    }
+
+    /// Opens the input to be read a chunk at a time, for inputs too large to
+    /// [`read`](InputSource::read) whole.
+    pub fn open(&self) -> Result<Box<dyn BufRead>, Error> {
+        match self {
+            InputSource::Stdin => Ok(Box::new(io::stdin().lock())),
+            InputSource::Path(path) => match File::open(path) {
+                Ok(file) => Ok(Box::new(BufReader::new(file))),
+                Err(source) => Err(Error::Io {
+                    path: path.clone(),
+                    source,
+                }),
+            },
+            InputSource::Embedded(input) => Ok(Box::new(input.as_bytes())),
+        }
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+            InputSource::Embedded(_) => write!(f, "<embedded>"),
+        }
+    }
+}
+
+/// The calling crate's `extra/input`, embedded into the binary when the crate
+/// is built with its `embed-input` feature and read from disk otherwise.
+#[macro_export]
+macro_rules! default_input {
+    () => {{
+        #[cfg(feature = "embed-input")]
+        let source = $crate::InputSource::Embedded(include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/extra/input"
+        )));
+        #[cfg(not(feature = "embed-input"))]
+        let source =
+            $crate::InputSource::Path(concat!(env!("CARGO_MANIFEST_DIR"), "/extra/input").into());
+        source
+    }};
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_arg() {
+        let default = InputSource::Embedded("1 2");
+        assert_eq!(
+            InputSource::from_arg(Some("-".into()), default.clone()),
+            InputSource::Stdin
+        );
+        assert_eq!(
+            InputSource::from_arg(Some("extra/input".into()), default.clone()),
+            InputSource::Path("extra/input".into())
+        );
+        assert_eq!(InputSource::from_arg(None, default.clone()), default);
+    }
+
+    #[test]
+    fn test_read() {
+        assert_eq!(InputSource::Embedded("1 2").read().unwrap(), "1 2");
+        let err = InputSource::Path("does/not/exist".into())
+            .read()
+            .unwrap_err();
+        assert!(matches!(err, Error::Io { .. }));
+    }
+
+    #[test]
+    fn test_open() {
+        let mut input = String::new();
+        InputSource::Embedded("1 2")
+            .open()
+            .unwrap()
+            .read_to_string(&mut input)
+            .unwrap();
+        assert_eq!(input, "1 2");
+        let err = InputSource::Path("does/not/exist".into()).open().err();
+        assert!(matches!(err, Some(Error::Io { .. })));
+    }
+}
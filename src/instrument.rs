@@ -0,0 +1,139 @@
+//! Timing and, with the `count-alloc` feature, peak heap usage of a closure.
+
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "count-alloc")]
+mod counting {
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    static CURRENT: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    /// The system allocator, keeping track of live and peak heap bytes.
+    pub struct CountingAlloc;
+
+    fn grow(bytes: usize) {
+        let current = CURRENT.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn shrink(bytes: usize) {
+        CURRENT.fetch_sub(bytes, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                grow(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                grow(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            shrink(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                if new_size > layout.size() {
+                    grow(new_size - layout.size());
+                } else {
+                    shrink(layout.size() - new_size);
+                }
+            }
+            new
+        }
+    }
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    /// Starts a new measurement, returning the bytes live at its start.
+    pub fn start() -> usize {
+        let current = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+        current
+    }
+
+    /// Highest number of bytes live since [`start`] on top of `baseline`.
+    pub fn peak_since(baseline: usize) -> usize {
+        PEAK.load(Ordering::Relaxed).saturating_sub(baseline)
+    }
+}
+
+/// Result of a closure along with what it cost to compute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Measured<T> {
+    pub value: T,
+    pub elapsed: Duration,
+    /// Peak heap bytes allocated on top of what was live beforehand, only
+    /// known when built with the `count-alloc` feature.
+    pub peak_bytes: Option<usize>,
+}
+
+impl<T> Measured<T> {
+    /// Separates the value from its measurements.
+    pub fn split(self) -> (T, Measured<()>) {
+        let Measured {
+            value,
+            elapsed,
+            peak_bytes,
+        } = self;
+        let cost = Measured {
+            value: (),
+            elapsed,
+            peak_bytes,
+        };
+        (value, cost)
+    }
+}
+
+/// Runs `f`, timing it and tracking its peak heap usage. Measurements track
+/// the whole process, so they should neither nest nor run concurrently.
+pub fn measure<T>(f: impl FnOnce() -> T) -> Measured<T> {
+    #[cfg(feature = "count-alloc")]
+    let baseline = counting::start();
+    let start = Instant::now();
+    let value = f();
+    let elapsed = start.elapsed();
+
+    #[cfg(feature = "count-alloc")]
+    let peak_bytes = Some(counting::peak_since(baseline));
+    #[cfg(not(feature = "count-alloc"))]
+    let peak_bytes = None;
+    Measured {
+        value,
+        elapsed,
+        peak_bytes,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let measured = measure(|| vec![0u8; 1 << 20].len());
+        assert_eq!(measured.value, 1 << 20);
+        if cfg!(feature = "count-alloc") {
+            assert!(measured.peak_bytes.unwrap() >= 1 << 20);
+        } else {
+            assert_eq!(measured.peak_bytes, None);
+        }
+    }
+}
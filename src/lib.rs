@@ -1,6 +1,290 @@
-use std::{env, fs::File, io::BufReader};
+use std::{
+    env,
+    fmt::{self, Display},
+    fs::File,
+    io::BufReader,
+    path::PathBuf,
+    process::ExitCode,
+};
+
+mod cmd_line;
+mod error;
+pub mod examples;
+mod input;
+pub mod instrument;
+
+pub use cmd_line::CmdLine;
+pub use error::{Error, ParseError};
+pub use input::InputSource;
+use instrument::{measure, Measured};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl TryFrom<u8> for Part {
+    type Error = u8;
+
+    fn try_from(n: u8) -> Result<Self, Self::Error> {
+        match n {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            n => Err(n),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Part::One => "1",
+            Part::Two => "2",
+        })
+    }
+}
+
+/// A day's puzzle: parse the input once, then answer both parts from it.
+pub trait Solution {
+    const DAY: u8;
+
+    type Input;
+
+    /// Where the input comes from when none is given, usually
+    /// [`default_input!`].
+    fn default_input() -> InputSource;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> impl Display;
+    fn part2(input: &Self::Input) -> impl Display;
+
+    fn solve(input: &Self::Input, part: Part) -> String {
+        match part {
+            Part::One => Self::part1(input).to_string(),
+            Part::Two => Self::part2(input).to_string(),
+        }
+    }
+}
+
+fn cmd_line_path() -> Result<PathBuf, Error> {
+    env::args_os()
+        .nth(1)
+        .map(PathBuf::from)
+        .ok_or(Error::MissingArg)
+}
+
+pub fn try_open_file_cmd_line() -> Result<BufReader<File>, Error> {
+    let path = cmd_line_path()?;
+    match File::open(&path) {
+        Ok(file) => Ok(BufReader::new(file)),
+        Err(source) => Err(Error::Io { path, source }),
+    }
+}
 
 pub fn open_file_cmd_line() -> BufReader<File> {
-    let filepath = env::args().nth(1).unwrap();
-    File::open(filepath).map(BufReader::new).unwrap()
-}
\ No newline at end of file
+    try_open_file_cmd_line().unwrap()
+}
+
+/// A part solved by one of the `partNN` binaries, with what each phase cost.
+struct Run {
+    day: u8,
+    part: Part,
+    answer: String,
+    parse: Measured<()>,
+    solve: Measured<()>,
+}
+
+fn run<S: Solution>(cmd: &CmdLine, part: Part) -> Result<Run, Error> {
+    let input = cmd.source(S::default_input()).read()?;
+    let (parsed, parse) = measure(|| S::parse(&input)).split();
+    let parsed = parsed?;
+    let (answer, solve) = measure(|| S::solve(&parsed, part)).split();
+    Ok(Run {
+        day: S::DAY,
+        part,
+        answer,
+        parse,
+        solve,
+    })
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Run {
+    fn phase_json(phase: &Measured<()>) -> String {
+        let peak = phase
+            .peak_bytes
+            .map_or_else(|| "null".to_owned(), |bytes| bytes.to_string());
+        format!(
+            "{{\"nanos\":{},\"peak_bytes\":{peak}}}",
+            phase.elapsed.as_nanos()
+        )
+    }
+
+    fn to_json(&self) -> String {
+        format!(
+            "{{\"day\":{},\"part\":{},\"answer\":{},\"parse\":{},\"solve\":{}}}",
+            self.day,
+            self.part,
+            json_string(&self.answer),
+            Self::phase_json(&self.parse),
+            Self::phase_json(&self.solve),
+        )
+    }
+
+    fn print_stats(&self) {
+        for (name, phase) in [("parse", &self.parse), ("solve", &self.solve)] {
+            match phase.peak_bytes {
+                Some(bytes) => eprintln!(
+                    "{name}: {:.3?}, peak heap {}",
+                    phase.elapsed,
+                    format_bytes(bytes)
+                ),
+                None => eprintln!("{name}: {:.3?}", phase.elapsed),
+            }
+        }
+    }
+}
+
+fn exit_code(result: Result<(), Error>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_cmd<S: Solution>(cmd: &CmdLine, part: Part) -> Result<(), Error> {
+    cmd.expect_flags(&["--json"])?;
+    let run = run::<S>(cmd, part)?;
+    if cmd.flag("--json") {
+        println!("{}", run.to_json());
+    } else {
+        println!("{}", run.answer);
+        run.print_stats();
+    }
+    Ok(())
+}
+
+/// Entry point shared by the `partNN` binaries of every day. The input is the
+/// first argument, `-` for stdin, or the day's default input when omitted.
+/// The answer goes to stdout and timings to stderr, or everything to stdout
+/// as a JSON object with `--json`.
+pub fn run_part<S: Solution>(part: Part) -> ExitCode {
+    exit_code(CmdLine::from_env().and_then(|cmd| run_cmd::<S>(&cmd, part)))
+}
+
+/// [`run_part`] for binaries with an extra mode: when `flag` is given, the
+/// input and what it parses to are handed to `mode` along with the flag's
+/// value, if any, instead of solving `part`.
+pub fn run_part_with<S: Solution>(
+    part: Part,
+    flag: &str,
+    mode: impl FnOnce(&str, &S::Input, Option<&str>) -> Result<(), Error>,
+) -> ExitCode {
+    exit_code(CmdLine::from_env().and_then(|cmd| run_cmd_with::<S>(&cmd, part, flag, mode)))
+}
+
+fn run_cmd_with<S: Solution>(
+    cmd: &CmdLine,
+    part: Part,
+    flag: &str,
+    mode: impl FnOnce(&str, &S::Input, Option<&str>) -> Result<(), Error>,
+) -> Result<(), Error> {
+    if !cmd.flag(flag) {
+        return run_cmd::<S>(cmd, part);
+    }
+    cmd.expect_flags(&[flag])?;
+    let input = cmd.source(S::default_input()).read()?;
+    let parsed = S::parse(&input)?;
+    mode(&input, &parsed, cmd.value(flag))
+}
+
+/// [`run_part_with`] for days that can also solve `part` while reading the
+/// input, so that it never has to fit in memory: with `--stream`, `stream`
+/// is handed the input source to [`open`](InputSource::open) and the answer
+/// it returns is printed, without timings.
+pub fn run_part_streaming<S: Solution, A: Display>(
+    part: Part,
+    flag: &str,
+    mode: impl FnOnce(&str, &S::Input, Option<&str>) -> Result<(), Error>,
+    stream: impl FnOnce(&InputSource) -> Result<A, Error>,
+) -> ExitCode {
+    exit_code(CmdLine::from_env().and_then(|cmd| {
+        if !cmd.flag("--stream") {
+            return run_cmd_with::<S>(&cmd, part, flag, mode);
+        }
+        cmd.expect_flags(&["--stream"])?;
+        println!("{}", stream(&cmd.source(S::default_input()))?);
+        Ok(())
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(12), "12 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_run_json() {
+        let phase = Measured {
+            value: (),
+            elapsed: std::time::Duration::from_nanos(1500),
+            peak_bytes: None,
+        };
+        let run = Run {
+            day: 3,
+            part: Part::Two,
+            answer: "a\"b".to_owned(),
+            parse: phase,
+            solve: Measured {
+                peak_bytes: Some(64),
+                ..phase
+            },
+        };
+        assert_eq!(
+            run.to_json(),
+            r#"{"day":3,"part":2,"answer":"a\"b","parse":{"nanos":1500,"peak_bytes":null},"solve":{"nanos":1500,"peak_bytes":64}}"#
+        );
+    }
+}